@@ -0,0 +1,74 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"Columns",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "Columns");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	// a flattened field spans several columns, it has no single column identifier
+	let cols: Vec<_> = crate::util::cols(struct_fields, &tab_attr)?
+		.into_iter()
+		.filter(|c| !c.is_flatten())
+		.collect();
+	let enum_name = crate::util::col_enum_name(&generator.target_name().to_string());
+
+	if !cols.is_empty() {
+		let target_name = generator.target_name().to_string();
+		let mut gen_enum = generator.generate_enum(&enum_name);
+		gen_enum
+			.make_pub()
+			.with_parsed_attribute(format!("doc = \"Column identifiers of {target_name}\""))?
+			.with_derives(["Clone", "Copy", "Debug", "PartialEq", "Eq", "Hash"]);
+		for col in &cols {
+			gen_enum
+				.add_value(crate::util::col_variant(col.field_name()))
+				.make_zst();
+		}
+		drop(gen_enum);
+
+		generator
+			.impl_for_other_type(enum_name.as_str())
+			.generate_fn("name")
+			.make_pub()
+			.with_self_arg(FnSelfArg::TakeSelf)
+			.with_return_type("&'static str")
+			.body(|fn_body| {
+				let mut s = String::from("match self {");
+				for col in &cols {
+					s.push_str(&format!(
+						"Self::{} => \"{}\",",
+						crate::util::col_variant(col.field_name()),
+						col.name
+					));
+				}
+				s.push('}');
+				fn_body.push_parsed(s)?;
+				Ok(())
+			})?;
+	}
+
+	Ok(())
+}
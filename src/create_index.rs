@@ -1,6 +1,6 @@
 use virtue::{
 	parse::Attribute,
-	prelude::{AttributeAccess, Body, Fields, Generator, Parse, Result, TokenStream},
+	prelude::{Body, Error, Fields, Generator, Parse, Result, TokenStream},
 };
 
 pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
@@ -8,7 +8,12 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
 		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"CreateIndexSql",
+			));
+		}
 	};
 	generator.export_to_file("wb_sqlite", "CreateIndexSql");
 	generator.finish()
@@ -16,37 +21,102 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 
 fn gen_struct(
 	generator: &mut Generator,
-	_attributes: Vec<Attribute>,
+	attributes: Vec<Attribute>,
 	fields: Option<Fields>,
 ) -> Result {
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let raw_name = crate::util::table_name(&generator.target_name().to_string(), &tab_attr);
+	let quote = |name: &str| crate::util::quote(name, tab_attr.quote_all);
+	let tab_name = quote(&raw_name);
 
-	let mut create_index = String::new();
-	for (ident, uf) in struct_fields {
-		let col_attr = uf
-			.attributes
-			.get_attribute::<crate::util::ColAttr>()?
-			.unwrap_or_default();
-		if col_attr.constraint.starts_with("REFERENCES ") {
-			let col_name = ident.to_string();
-			create_index.push_str(&format!(
-				"CREATE INDEX {tab_name}_{col_name}_idx ON {tab_name}({col_name}); "
-			));
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	// (index-name, UNIQUE, indexed columns, WHERE expr)
+	let mut indexes: Vec<(String, bool, Vec<String>, String)> = Vec::new();
+	for col in &cols {
+		let mut names = Vec::with_capacity(2);
+		if col.attr.is_fk() {
+			names.push(String::new());
+		}
+		if let Some(name) = &col.attr.index {
+			names.push(name.clone());
+		}
+		for name in names {
+			let name = if name.is_empty() {
+				format!("{raw_name}_{}_idx", col.name)
+			} else {
+				name
+			};
+			// fields with the same index-name make one multi-column index
+			match indexes.iter_mut().find(|index| index.0 == name) {
+				Some(index) if index.2.contains(&col.ident()) => {}
+				Some(index) => index.2.push(col.ident()),
+				None => indexes.push((name, false, vec![col.ident()], String::new())),
+			}
+		}
+	}
+	for index in &tab_attr.index {
+		let items = crate::util::split_list(&index.cols);
+		let name = if index.name.is_empty() {
+			let parts: Vec<String> = items.iter().map(|i| crate::util::name_part(i)).collect();
+			format!("{raw_name}_{}_idx", parts.join("_"))
+		} else {
+			index.name.clone()
+		};
+		if indexes.iter().any(|i| i.0 == name) {
+			return Err(Error::custom(format!("duplicate index name {name}")));
 		}
+		// a column by field-name or column-name, else an expression
+		let exprs = items
+			.iter()
+			.map(|item| {
+				match cols
+					.iter()
+					.find(|c| !c.is_flatten() && (c.field_name() == *item || c.name == *item))
+				{
+					Some(col) => col.ident(),
+					None => item.to_string(),
+				}
+			})
+			.collect();
+		indexes.push((name, index.unique, exprs, index.filter.clone()));
+	}
+
+	let mut create_index = String::new();
+	let mut drop_index = String::new();
+	for (name, unique, exprs, filter) in indexes {
+		let index_name = quote(&name);
+		let unique = if unique { "UNIQUE " } else { "" };
+		let filter = if filter.is_empty() {
+			String::new()
+		} else {
+			format!(" WHERE {filter}")
+		};
+		create_index.push_str(&format!(
+			"CREATE {unique}INDEX {index_name} ON {tab_name}({}){filter}; ",
+			exprs.join(",")
+		));
+		drop_index.push_str(&format!("DROP INDEX {index_name}; "));
 	}
 
 	if !create_index.is_empty() {
-		generator
-			.generate_impl()
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
 			.generate_const("CREATE_INDEX_SQL", "&'static str")
 			.make_pub()
 			.with_value(|b| {
 				b.push_parsed(format!("\"{create_index}\""))?;
 				Ok(())
 			})?;
+		gen_impl
+			.generate_const("DROP_INDEX_SQL", "&'static str")
+			.make_pub()
+			.with_value(|b| {
+				b.push_parsed(format!("\"{drop_index}\""))?;
+				Ok(())
+			})?;
 	}
 
 	Ok(())
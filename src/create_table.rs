@@ -1,14 +1,17 @@
 use virtue::{
-	parse::Attribute,
-	prelude::{AttributeAccess, Body, Fields, Generator, Parse, Result, TokenStream},
+	parse::{Attribute, EnumBody},
+	prelude::{Body, Fields, Generator, Parse, Result, TokenStream},
 };
 
 pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let parse = Parse::new(input)?;
+	let generic = crate::util::has_generics(&parse);
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
-		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Struct(struct_body) => {
+			gen_struct(&mut generator, attributes, struct_body.fields, generic)?
+		}
+		Body::Enum(enum_body) => gen_enum(&mut generator, attributes, enum_body)?,
 	};
 	generator.export_to_file("wb_sqlite", "CreateTableSql");
 	generator.finish()
@@ -18,14 +21,13 @@ fn gen_struct(
 	generator: &mut Generator,
 	attributes: Vec<Attribute>,
 	fields: Option<Fields>,
+	generic: bool,
 ) -> Result {
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
-	let tab_attr = attributes
-		.get_attribute::<crate::util::TabAttr>()?
-		.unwrap_or_default();
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let tab_name = crate::util::table_ident(&generator.target_name().to_string(), &tab_attr);
 	let tab_constraint = if tab_attr.constraint.is_empty() {
 		String::new()
 	} else {
@@ -37,40 +39,107 @@ fn gen_struct(
 		format!(", {}", tab_attr.option)
 	};
 
-	let mut col_defs = String::new();
-	for (ident, uf) in struct_fields {
-		let col_attr = uf
-			.attributes
-			.get_attribute::<crate::util::ColAttr>()?
-			.unwrap_or_default();
+	let mut col_defs = crate::util::Sql::default();
+	crate::util::integer_ranges(&struct_fields)?;
+	for col in crate::util::cols(struct_fields, &tab_attr)? {
+		let col_attr = &col.attr;
 		if !col_defs.is_empty() {
 			col_defs.push_str(", ");
 		}
-		col_defs.push_str(&ident.to_string());
-		col_defs.push(' ');
-		if col_attr.typ.is_empty() {
-			col_defs.push_str(crate::util::col_typ(&uf.type_string()));
+		if col.is_flatten() {
+			col_defs.push_flatten_defs(&col, true);
+			continue;
+		}
+		col_defs.push_str(&col.ident());
+		col_defs.push_str(" ");
+		if col_attr.version {
+			// optimistic locking, a new row starts at version 0
+			if col_attr.typ.is_empty() {
+				col_defs.push_str("INTEGER");
+			} else {
+				col_defs.push_str(&col_attr.typ);
+			}
+			col_defs.push_str(" NOT NULL DEFAULT 0");
+		} else if col_attr.typ.is_empty() {
+			col_defs.push_col_typ(&col.typ, false, generic);
+			if crate::util::is_json(&col.typ) {
+				col_defs.push_str(&format!(" CHECK (json_valid({}))", col.ident()));
+			}
+			match crate::util::typ_check(&col.typ) {
+				Some(check) if !col_attr.unchecked => {
+					col_defs.push_str(&format!(" CHECK ({} {check})", col.ident()));
+				}
+				_ => {}
+			}
 		} else {
 			col_defs.push_str(&col_attr.typ);
 		}
-		if !col_attr.constraint.is_empty() {
-			col_defs.push(' ');
-			col_defs.push_str(&col_attr.constraint);
+		let constraint = col_attr.sql_constraint();
+		if !constraint.is_empty() {
+			col_defs.push_str(" ");
+			col_defs.push_str(&constraint);
 		}
 	}
 
 	if !col_defs.is_empty() {
-		generator
-			.generate_impl()
+		let mut sql = crate::util::Sql::default();
+		sql.push_str(&format!("CREATE TABLE {tab_name} ("));
+		sql.push_sql(col_defs);
+		sql.push_str(&format!("{tab_constraint}) STRICT{tab_option};"));
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
 			.generate_const("CREATE_TABLE_SQL", "&'static str")
 			.make_pub()
 			.with_value(|b| {
-				b.push_parsed(format!(
-					"\"CREATE TABLE {tab_name} ({col_defs}{tab_constraint}) STRICT{tab_option};\""
-				))?;
+				b.push_parsed(sql.expr())?;
 				Ok(())
 			})?;
+		gen_impl
+			.generate_const("DROP_TABLE_SQL", "&'static str")
+			.make_pub()
+			.with_value(|b| {
+				b.push_parsed(format!("\"DROP TABLE {tab_name};\""))?;
+				Ok(())
+			})?;
+	}
+
+	Ok(())
+}
+
+/// Lookup table with one row per variant name.
+fn gen_enum(generator: &mut Generator, attributes: Vec<Attribute>, enum_body: EnumBody) -> Result {
+	let variants = crate::util::enum_variants(&generator.target_name(), enum_body)?;
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let tab_name = crate::util::table_ident(&generator.target_name().to_string(), &tab_attr);
+	let tab_option = if tab_attr.option.is_empty() {
+		String::new()
+	} else {
+		format!(", {}", tab_attr.option)
+	};
+
+	let mut values = String::new();
+	for v in &variants {
+		values.push_str(&format!("('{v}'),"));
 	}
+	values.pop(); // get rid of the last ','
+
+	let mut gen_impl = generator.generate_impl();
+	gen_impl
+		.generate_const("CREATE_TABLE_SQL", "&'static str")
+		.make_pub()
+		.with_value(|b| {
+			b.push_parsed(format!(
+				"\"CREATE TABLE {tab_name} (name TEXT NOT NULL PRIMARY KEY) STRICT{tab_option}; INSERT INTO {tab_name} (name) VALUES {values};\""
+			))?;
+			Ok(())
+		})?;
+	gen_impl
+		.generate_const("DROP_TABLE_SQL", "&'static str")
+		.make_pub()
+		.with_value(|b| {
+			b.push_parsed(format!("\"DROP TABLE {tab_name};\""))?;
+			Ok(())
+		})?;
 
 	Ok(())
 }
@@ -1,14 +1,22 @@
 use virtue::{
 	parse::Attribute,
-	prelude::{AttributeAccess, Body, Fields, Generator, Parse, Result, TokenStream},
+	prelude::{Body, Error, Fields, Generator, Parse, Result, TokenStream},
 };
 
 pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let parse = Parse::new(input)?;
+	let generic = crate::util::has_generics(&parse);
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
-		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Struct(struct_body) => {
+			gen_struct(&mut generator, attributes, struct_body.fields, generic)?
+		}
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"CreateTableLogSql",
+			));
+		}
 	};
 	generator.export_to_file("wb_sqlite", "CreateTableLogSql");
 	generator.finish()
@@ -16,57 +24,169 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 
 fn gen_struct(
 	generator: &mut Generator,
-	_attributes: Vec<Attribute>,
+	attributes: Vec<Attribute>,
 	fields: Option<Fields>,
+	generic: bool,
 ) -> Result {
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
-	let tab_log_name = format!("{tab_name}_log");
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let raw_name = crate::util::table_name(&generator.target_name().to_string(), &tab_attr);
+	let quote = |name: &str| crate::util::quote(name, tab_attr.quote_all);
+	let tab_name = quote(&raw_name);
+	let tab_log_name = quote(&format!("{raw_name}_log"));
+	let update_trigger = quote(&format!("{raw_name}_update"));
+	let delete_trigger = quote(&format!("{raw_name}_delete"));
+	let insert_trigger = quote(&format!("{raw_name}_insert"));
 
-	let mut col_defs = String::new();
-	let mut columns = String::new();
-	let mut log_values = String::new();
-	let mut create_index = String::new();
-	for (ident, uf) in struct_fields {
-		let col_attr = uf
-			.attributes
-			.get_attribute::<crate::util::ColAttr>()?
-			.unwrap_or_default();
+	crate::util::integer_ranges(&struct_fields)?;
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	if tab_attr.log_sparse {
+		// log_mask has one bit per column
+		if cols.iter().any(|c| c.is_flatten()) {
+			return Err(Error::custom("log_sparse does not support flatten fields"));
+		}
+		if cols.len() > 63 {
+			return Err(Error::custom("log_sparse supports at most 63 columns"));
+		}
+	}
+	// the field attribute pk or the table constraint PRIMARY KEY(a,b)
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+	let mut col_defs = crate::util::Sql::default();
+	for col in &cols {
 		if !col_defs.is_empty() {
 			col_defs.push_str(", ");
-			columns.push(',');
-			log_values.push(',');
 		}
-		let col_name = ident.to_string();
+		if col.is_flatten() {
+			col_defs.push_flatten_defs(col, false);
+			continue;
+		}
+		let col_name = col.ident();
 		col_defs.push_str(&col_name);
-		columns.push_str(&col_name);
-		log_values.push_str("OLD.");
-		log_values.push_str(&col_name);
-		col_defs.push(' ');
-		if col_attr.typ.is_empty() {
-			col_defs.push_str(crate::util::col_typ(&uf.type_string()));
+		col_defs.push_str(" ");
+		// NULL for an unchanged column
+		let nullable = tab_attr.log_sparse && !keys.is_pk(col);
+		if !col.attr.typ.is_empty() {
+			if nullable {
+				col_defs.push_str(&col.attr.typ.replace(" NOT NULL", ""));
+			} else {
+				col_defs.push_str(&col.attr.typ);
+			}
 		} else {
-			col_defs.push_str(&col_attr.typ);
-		}
-		if col_attr.constraint.starts_with("PRIMARY KEY") {
-			create_index =
-				format!("CREATE INDEX {tab_log_name}_{col_name}_idx ON {tab_log_name}({col_name}); ");
+			col_defs.push_col_typ(&col.typ, nullable, generic);
 		}
 	}
+	let (create_index, drop_index) = if keys.pk.is_empty() {
+		(String::new(), String::new())
+	} else {
+		let names: Vec<&str> = keys.pk.iter().map(|c| c.name.as_str()).collect();
+		let idents: Vec<String> = keys.pk.iter().map(|c| c.ident()).collect();
+		let index_name = quote(&format!("{raw_name}_log_{}_idx", names.join("_")));
+		(
+			format!(
+				"CREATE INDEX {index_name} ON {tab_log_name}({}); ",
+				idents.join(",")
+			),
+			format!("DROP INDEX {index_name}; "),
+		)
+	};
 
 	if !col_defs.is_empty() {
-		generator
-		.generate_impl()
-		.generate_const("CREATE_TABLE_LOG_SQL", "&'static str")
-		.make_pub()
-		.with_value(|b| {
-			b.push_parsed(format!(
-				"\"CREATE TABLE {tab_log_name} ({col_defs}) STRICT; {create_index}CREATE TRIGGER {tab_name}_update UPDATE ON {tab_name} BEGIN INSERT INTO {tab_log_name} ({columns}) VALUES ({log_values}); END; CREATE TRIGGER {tab_name}_delete DELETE ON {tab_name} BEGIN INSERT INTO {tab_log_name} ({columns}) VALUES ({log_values}); END;\""
-			))?;
-			Ok(())
-		})?;
+		let cols: Vec<&crate::util::Col> = cols.iter().collect();
+		let mut sql = crate::util::Sql::default();
+		sql.push_str(&format!("CREATE TABLE {tab_log_name} ("));
+		if tab_attr.log_audit {
+			// log_at with fractional seconds needs SQLite 3.42
+			sql.push_str("log_id INTEGER PRIMARY KEY AUTOINCREMENT, log_op TEXT NOT NULL CHECK (log_op IN ('I','U','D')), log_at REAL NOT NULL DEFAULT (unixepoch('subsec')), ");
+		}
+		if tab_attr.log_sparse {
+			sql.push_str("log_mask INTEGER NOT NULL, ");
+		}
+		sql.push_sql(col_defs);
+		sql.push_str(&format!(") STRICT; {create_index}"));
+		// the version column changes with every update of the crate
+		let compared: Vec<&crate::util::Col> =
+			cols.iter().copied().filter(|c| !c.attr.version).collect();
+		let mut drop_sql = String::new();
+		let mut triggers = Vec::with_capacity(3);
+		if tab_attr.log_insert {
+			// AFTER, the NEW rowid of a BEFORE INSERT trigger is not assigned yet
+			triggers.push((insert_trigger, "AFTER INSERT", "NEW.", "'I',"));
+		}
+		triggers.push((update_trigger, "UPDATE", "OLD.", "'U',"));
+		triggers.push((delete_trigger, "DELETE", "OLD.", "'D',"));
+		for (i, (trigger, event, row, op)) in triggers.into_iter().enumerate() {
+			let update = op == "'U',";
+			drop_sql.push_str(&format!("DROP TRIGGER {trigger}; "));
+			if i > 0 {
+				sql.push_str(" ");
+			}
+			sql.push_str(&format!("CREATE TRIGGER {trigger} {event} ON {tab_name} "));
+			if update && tab_attr.log_changed && !compared.is_empty() {
+				sql.push_str("WHEN ");
+				sql.push_cols_changed(&compared);
+				sql.push_str(" ");
+			}
+			sql.push_str(&format!("BEGIN INSERT INTO {tab_log_name} ("));
+			if tab_attr.log_audit {
+				sql.push_str("log_op,");
+			}
+			if tab_attr.log_sparse {
+				sql.push_str("log_mask,");
+			}
+			sql.push_cols(&cols, "", "", ",");
+			sql.push_str(") VALUES (");
+			if tab_attr.log_audit {
+				sql.push_str(op);
+			}
+			if tab_attr.log_sparse && update {
+				// bit i of log_mask is set if column i changed
+				let mask = cols
+					.iter()
+					.enumerate()
+					.map(|(i, c)| match i {
+						0 => format!("(OLD.{0} IS NOT NEW.{0})", c.ident()),
+						_ => format!("(OLD.{0} IS NOT NEW.{0})*{1}", c.ident(), 1_i64 << i),
+					})
+					.collect::<Vec<_>>()
+					.join("+");
+				sql.push_str(&mask);
+				for col in &cols {
+					if keys.is_pk(col) {
+						sql.push_str(&format!(",OLD.{}", col.ident()));
+					} else {
+						sql.push_str(&format!(
+							",CASE WHEN OLD.{0} IS NOT NEW.{0} THEN OLD.{0} END",
+							col.ident()
+						));
+					}
+				}
+			} else {
+				if tab_attr.log_sparse {
+					// INSERT and DELETE log the whole row
+					sql.push_str(&format!("{},", u64::MAX >> (64 - cols.len())));
+				}
+				sql.push_cols(&cols, row, "", ",");
+			}
+			sql.push_str("); END;");
+		}
+		drop_sql.push_str(&format!("{drop_index}DROP TABLE {tab_log_name};"));
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
+			.generate_const("CREATE_TABLE_LOG_SQL", "&'static str")
+			.make_pub()
+			.with_value(|b| {
+				b.push_parsed(sql.expr())?;
+				Ok(())
+			})?;
+		gen_impl
+			.generate_const("DROP_TABLE_LOG_SQL", "&'static str")
+			.make_pub()
+			.with_value(|b| {
+				b.push_parsed(format!("\"{drop_sql}\""))?;
+				Ok(())
+			})?;
 	}
 
 	Ok(())
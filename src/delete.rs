@@ -0,0 +1,80 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"Delete",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "Delete");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let tab_name = crate::util::table_ident(&generator.target_name().to_string(), &tab_attr);
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+
+	fn query(tab_name: &str, key: &[&crate::util::Col], val: &str) -> String {
+		let mut s = format!(
+			"let rows = ::sqlx::query(\"DELETE FROM {tab_name} WHERE {}\")",
+			crate::util::key_where(key)
+		);
+		for col in key {
+			s.push_str(&format!(".bind({val}{})", col.field));
+		}
+		s.push_str(".execute(exec).await?.rows_affected(); Ok(rows == 1)");
+		s
+	}
+
+	if keys.all().next().is_some() {
+		let mut gen_impl = generator.generate_impl();
+		if !keys.pk.is_empty() {
+			gen_impl
+				.generate_fn("delete")
+				.as_async()
+				.with_self_arg(FnSelfArg::RefSelf)
+				.with_arg("exec", "impl ::sqlx::SqliteExecutor<'_>")
+				.with_return_type("Result<bool, ::sqlx::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					fn_body.push_parsed(query(&tab_name, &keys.pk, "&self."))?;
+					Ok(())
+				})?;
+		}
+		for key in keys.all() {
+			let mut f = gen_impl
+				.generate_fn(format!("delete_by_{}", crate::util::key_name(key)))
+				.as_async();
+			for col in key {
+				f = f.with_arg(&col.field, col.arg_typ());
+			}
+			f.with_arg("exec", "impl ::sqlx::SqliteExecutor<'_>")
+				.with_return_type("Result<bool, ::sqlx::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					fn_body.push_parsed(query(&tab_name, key, ""))?;
+					Ok(())
+				})?;
+		}
+	}
+
+	Ok(())
+}
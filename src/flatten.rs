@@ -0,0 +1,74 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"Flatten",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "Flatten");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let skipped = crate::util::skipped_fields(&struct_fields)?;
+	let cols = crate::util::flatten_cols(struct_fields, &tab_attr)?;
+	let query = "::sqlx::query::Query<'q, ::sqlx::Sqlite, ::sqlx::sqlite::SqliteArguments<'q>>";
+
+	if !cols.is_empty() {
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
+			.generate_fn("flatten_bind")
+			.with_lifetime("q")
+			.with_arg("self", "&'q Self")
+			.with_arg("query", query)
+			.with_return_type(query)
+			.make_pub()
+			.body(|fn_body| {
+				let mut s = String::from("query");
+				for col in &cols {
+					s.push_str(&format!(".bind(&self.{})", col.field));
+				}
+				fn_body.push_parsed(s)?;
+				Ok(())
+			})?;
+		gen_impl
+			.generate_fn("flatten_from_row")
+			.with_arg("row", "&::sqlx::sqlite::SqliteRow")
+			.with_arg("prefix", "&str")
+			.with_return_type("Result<Self, ::sqlx::Error>")
+			.make_pub()
+			.body(|fn_body| {
+				let mut s = String::from("Ok(Self {");
+				for col in &cols {
+					s.push_str(&format!(
+						"{}: ::sqlx::Row::try_get(row, format!(\"{{prefix}}{}\").as_str())?,",
+						col.field, col.name
+					));
+				}
+				s.push_str(&crate::util::default_fields(&skipped));
+				s.push_str("})");
+				fn_body.push_parsed(s)?;
+				Ok(())
+			})?;
+	}
+
+	Ok(())
+}
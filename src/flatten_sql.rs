@@ -0,0 +1,90 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let generic = crate::util::has_generics(&parse);
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => {
+			gen_struct(&mut generator, attributes, struct_body.fields, generic)?
+		}
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"FlattenSql",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "FlattenSql");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+	generic: bool,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	crate::util::integer_ranges(&struct_fields)?;
+	let cols = crate::util::flatten_cols(struct_fields, &tab_attr)?;
+
+	let mut columns = String::new();
+	let mut col_types = String::new();
+	let mut col_defs = String::new();
+	let mut col_checks = String::new();
+	for col in &cols {
+		let mut typ = crate::util::Sql::default();
+		if col.attr.typ.is_empty() {
+			typ.push_col_typ(&col.typ, false, generic);
+		} else {
+			typ.push_str(&col.attr.typ);
+		}
+		let constraint = col.attr.sql_constraint();
+		columns.push_str(&format!("\"{}\",", col.name));
+		col_types.push_str(&format!("{},", typ.expr()));
+		if !constraint.is_empty() {
+			typ.push_str(" ");
+			typ.push_str(&constraint);
+		}
+		col_defs.push_str(&format!("{},", typ.expr()));
+		// the checks of CreateTableSql, ? is replaced by the prefixed column name
+		let mut checks = Vec::new();
+		if col.attr.typ.is_empty() {
+			if crate::util::is_json(&col.typ) {
+				checks.push("CHECK (json_valid(?))".to_string());
+			}
+			match crate::util::typ_check(&col.typ) {
+				Some(check) if !col.attr.unchecked => checks.push(format!("CHECK (? {check})")),
+				_ => {}
+			}
+		}
+		col_checks.push_str(&format!("\"{}\",", checks.join(" ")));
+	}
+
+	if !cols.is_empty() {
+		let mut gen_impl = generator.generate_impl();
+		for (name, value) in [
+			("FLATTEN_COLUMNS", columns),
+			("FLATTEN_COL_TYPES", col_types),
+			("FLATTEN_COL_DEFS", col_defs),
+			("FLATTEN_COL_CHECKS", col_checks),
+		] {
+			gen_impl
+				.generate_const(name, "&'static [&'static str]")
+				.make_pub()
+				.with_value(|b| {
+					b.push_parsed(format!("&[{value}]"))?;
+					Ok(())
+				})?;
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,88 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"FlattenSync",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "FlattenSync");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let skipped = crate::util::skipped_fields(&struct_fields)?;
+	let cols = crate::util::flatten_cols(struct_fields, &tab_attr)?;
+
+	if !cols.is_empty() {
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
+			.generate_fn("flatten_params")
+			.with_lifetime("a")
+			.with_arg("self", "&'a Self")
+			.with_arg("params", "&mut Vec<&'a dyn ::rusqlite::ToSql>")
+			.make_pub()
+			.body(|fn_body| {
+				let mut s = String::new();
+				for col in &cols {
+					s.push_str(&format!("params.push(&self.{});", col.field));
+				}
+				fn_body.push_parsed(s)?;
+				Ok(())
+			})?;
+		gen_impl
+			.generate_fn("flatten_from_row_sync")
+			.with_arg("row", "&::rusqlite::Row<'_>")
+			.with_arg("idx", "usize")
+			.with_return_type("::rusqlite::Result<Self>")
+			.make_pub()
+			.body(|fn_body| {
+				let mut s = String::from("Ok(Self {");
+				for (i, col) in cols.iter().enumerate() {
+					s.push_str(&format!("{}: row.get(idx + {i})?,", col.field));
+				}
+				s.push_str(&crate::util::default_fields(&skipped));
+				s.push_str("})");
+				fn_body.push_parsed(s)?;
+				Ok(())
+			})?;
+		gen_impl
+			.generate_fn("flatten_from_row_by_name_sync")
+			.with_arg("row", "&::rusqlite::Row<'_>")
+			.with_arg("prefix", "&str")
+			.with_return_type("::rusqlite::Result<Self>")
+			.make_pub()
+			.body(|fn_body| {
+				let mut s = String::from("Ok(Self {");
+				for col in &cols {
+					s.push_str(&format!(
+						"{}: row.get(format!(\"{{prefix}}{}\").as_str())?,",
+						col.field, col.name
+					));
+				}
+				s.push_str(&crate::util::default_fields(&skipped));
+				s.push_str("})");
+				fn_body.push_parsed(s)?;
+				Ok(())
+			})?;
+	}
+
+	Ok(())
+}
@@ -0,0 +1,60 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"FromRowSync",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "FromRowSync");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let skipped = crate::util::skipped_fields(&struct_fields)?;
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+
+	if !cols.is_empty() {
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
+			.generate_fn("from_row_sync")
+			.with_arg("row", "&::rusqlite::Row<'_>")
+			.with_return_type("::rusqlite::Result<Self>")
+			.make_pub()
+			.body(|fn_body| {
+				fn_body.push_parsed(format!("Ok({})", crate::util::row_to_self(&cols, &skipped)))?;
+				Ok(())
+			})?;
+		gen_impl
+			.generate_fn("from_row_by_name_sync")
+			.with_arg("row", "&::rusqlite::Row<'_>")
+			.with_return_type("::rusqlite::Result<Self>")
+			.make_pub()
+			.body(|fn_body| {
+				fn_body.push_parsed(format!(
+					"Ok({})",
+					crate::util::row_to_self_by_name(&cols, &skipped)
+				))?;
+				Ok(())
+			})?;
+	}
+
+	Ok(())
+}
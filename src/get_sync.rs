@@ -0,0 +1,87 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_text(
+				&generator.target_name(),
+				"GetSync",
+				"TextEnumSync",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "GetSync");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let tab_name = crate::util::table_ident(&generator.target_name().to_string(), &tab_attr);
+	let skipped = crate::util::skipped_fields(&struct_fields)?;
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+	let row_to_self = crate::util::row_to_self(&cols, &skipped);
+
+	let query = |key: &[&crate::util::Col]| {
+		let params = key
+			.iter()
+			.map(|c| c.field.as_str())
+			.collect::<Vec<_>>()
+			.join(",");
+		let mut sql = crate::util::Sql::default();
+		sql.push_str("SELECT ");
+		sql.push_sql(crate::util::select_list(&cols));
+		sql.push_str(&format!(
+			" FROM {tab_name} WHERE {}",
+			crate::util::key_where(key)
+		));
+		format!(
+			"let mut stmt = conn.prepare_cached({})?; stmt.query_row(::rusqlite::params![{params}], |row| Ok({row_to_self}))",
+			sql.expr()
+		)
+	};
+
+	let get_keys = keys.get_keys();
+	if !get_keys.is_empty() {
+		let mut gen_impl = generator.generate_impl();
+		for key in get_keys {
+			let is_i64_pk = matches!(key.as_slice(), [c] if keys.is_pk(c) && c.is_i64());
+			let mut f = gen_impl.generate_fn(format!("get_by_{}_sync", crate::util::key_name(key)));
+			for col in key {
+				f = f.with_arg(&col.field, col.arg_typ());
+			}
+			f.with_arg("conn", "&::rusqlite::Connection")
+				.with_return_type("Result<Self, ::rusqlite::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					let mut s = String::new();
+					if is_i64_pk {
+						s.push_str(&format!(
+							"if {} < 1 {{Err(::rusqlite::Error::QueryReturnedNoRows)}} else {{",
+							key[0].field
+						));
+					}
+					s.push_str(&query(key));
+					if is_i64_pk {
+						s.push('}');
+					}
+					fn_body.push_parsed(s)?;
+					Ok(())
+				})?;
+		}
+	}
+	Ok(())
+}
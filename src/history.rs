@@ -0,0 +1,122 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Error, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"History",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "History");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let target_name = generator.target_name().to_string();
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	if tab_attr.log_sparse {
+		return Err(Error::custom(
+			"log_sparse stores NULL for unchanged columns, History needs the whole rows",
+		));
+	}
+	let tab_name = crate::util::table_ident(&target_name, &tab_attr);
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+	let key = &keys.pk;
+	if key.is_empty() {
+		return Ok(());
+	}
+	let key_name = crate::util::key_name(key);
+	let (history, as_of) = crate::util::history_sql(&target_name, &tab_attr, &cols, key);
+	let bind_key: String = key.iter().map(|c| format!(".bind(&{})", c.field)).collect();
+	let args: String = key.iter().map(|c| format!("{},", c.field)).collect();
+
+	let mut gen_impl = generator.generate_impl();
+	let mut f = gen_impl
+		.generate_fn(format!("history_by_{key_name}"))
+		.as_async();
+	for col in key {
+		f = f.with_arg(&col.field, col.arg_typ());
+	}
+	f.with_arg("exec", "impl ::sqlx::SqliteExecutor<'_>")
+		.with_return_type("Result<Vec<Self>, ::sqlx::Error>")
+		.make_pub()
+		.body(|fn_body| {
+			fn_body.push_parsed(format!(
+				"::sqlx::query_as::<_, Self>({}){bind_key}.fetch_all(exec).await",
+				history.expr()
+			))?;
+			Ok(())
+		})?;
+
+	let Some(as_of) = as_of else {
+		return Ok(());
+	};
+	let mut f = gen_impl
+		.generate_fn(format!("as_of_by_{key_name}"))
+		.as_async();
+	for col in key {
+		f = f.with_arg(&col.field, col.arg_typ());
+	}
+	f.with_arg("at", "f64")
+		.with_arg("exec", "impl ::sqlx::SqliteExecutor<'_>")
+		.with_return_type("Result<Option<Self>, ::sqlx::Error>")
+		.make_pub()
+		.body(|fn_body| {
+			fn_body.push_parsed(format!(
+				"::sqlx::query_as::<_, Self>({}){bind_key}.bind(at){bind_key}.fetch_optional(exec).await",
+				as_of.expr()
+			))?;
+			Ok(())
+		})?;
+
+	if tab_attr.restore {
+		// the restored row replaces the current version
+		let ver = match crate::util::version_col(&cols) {
+			None => String::new(),
+			Some(ver) => format!(
+				"let Some(ver) = ::sqlx::query(\"SELECT {} FROM {tab_name} WHERE {}\"){bind_key}.fetch_optional(&mut *tx).await? else {{ return Ok(false); }}; let ver = ::sqlx::Row::try_get(&ver, 0)?;",
+				ver.ident(),
+				crate::util::key_where(key)
+			),
+		};
+		let set_ver = match crate::util::version_col(&cols) {
+			None => String::new(),
+			Some(ver) => format!("row.{} = ver;", ver.field),
+		};
+		let mut f = gen_impl
+			.generate_fn(format!("restore_by_{key_name}"))
+			.as_async()
+			.with_lifetime("c");
+		for col in key {
+			f = f.with_arg(&col.field, col.arg_typ());
+		}
+		f.with_arg("at", "f64")
+			.with_arg("conn", "impl ::sqlx::Acquire<'c, Database = ::sqlx::Sqlite>")
+			.with_return_type("Result<bool, ::sqlx::Error>")
+			.make_pub()
+			.body(|fn_body| {
+				fn_body.push_parsed(format!(
+					"let mut tx = conn.begin().await?; {ver} let Some(mut row) = Self::as_of_by_{key_name}({args} at, &mut *tx).await? else {{ return Ok(false); }}; {set_ver} let updated = row.update(&mut *tx).await?; tx.commit().await?; Ok(updated)"
+				))?;
+				Ok(())
+			})?;
+	}
+
+	Ok(())
+}
@@ -0,0 +1,122 @@
+use virtue::{
+	parse::Attribute,
+	prelude::{Body, Error, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"HistorySync",
+			));
+		}
+	};
+	generator.export_to_file("wb_sqlite", "HistorySync");
+	generator.finish()
+}
+
+fn gen_struct(
+	generator: &mut Generator,
+	attributes: Vec<Attribute>,
+	fields: Option<Fields>,
+) -> Result {
+	let Some(Fields::Struct(struct_fields)) = fields else {
+		return Ok(());
+	};
+	let target_name = generator.target_name().to_string();
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	if tab_attr.log_sparse {
+		return Err(Error::custom(
+			"log_sparse stores NULL for unchanged columns, History needs the whole rows",
+		));
+	}
+	let raw_name = crate::util::table_name(&target_name, &tab_attr);
+	let tab_name = crate::util::quote(&raw_name, tab_attr.quote_all);
+	let savepoint = crate::util::quote(&format!("{raw_name}_restore"), tab_attr.quote_all);
+	let skipped = crate::util::skipped_fields(&struct_fields)?;
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+	let key = &keys.pk;
+	if key.is_empty() {
+		return Ok(());
+	}
+	let key_name = crate::util::key_name(key);
+	let (history, as_of) = crate::util::history_sql(&target_name, &tab_attr, &cols, key);
+	let row_to_self = crate::util::row_to_self(&cols, &skipped);
+	let params = key
+		.iter()
+		.map(|c| c.field.as_str())
+		.collect::<Vec<_>>()
+		.join(",");
+
+	let mut gen_impl = generator.generate_impl();
+	let mut f = gen_impl.generate_fn(format!("history_by_{key_name}_sync"));
+	for col in key {
+		f = f.with_arg(&col.field, col.arg_typ());
+	}
+	f.with_arg("conn", "&::rusqlite::Connection")
+		.with_return_type("Result<Vec<Self>, ::rusqlite::Error>")
+		.make_pub()
+		.body(|fn_body| {
+			fn_body.push_parsed(format!(
+				"let mut stmt = conn.prepare_cached({})?; let rows = stmt.query_map(::rusqlite::params![{params}], |row| Ok({row_to_self}))?; rows.collect()",
+				history.expr()
+			))?;
+			Ok(())
+		})?;
+
+	let Some(as_of) = as_of else {
+		return Ok(());
+	};
+	let mut f = gen_impl.generate_fn(format!("as_of_by_{key_name}_sync"));
+	for col in key {
+		f = f.with_arg(&col.field, col.arg_typ());
+	}
+	f.with_arg("at", "f64")
+		.with_arg("conn", "&::rusqlite::Connection")
+		.with_return_type("Result<Option<Self>, ::rusqlite::Error>")
+		.make_pub()
+		.body(|fn_body| {
+			fn_body.push_parsed(format!(
+				"let mut stmt = conn.prepare_cached({})?; ::rusqlite::OptionalExtension::optional(stmt.query_row(::rusqlite::params![{params}, at, {params}], |row| Ok({row_to_self})))",
+				as_of.expr()
+			))?;
+			Ok(())
+		})?;
+
+	if tab_attr.restore {
+		// the restored row replaces the current version
+		let ver = match crate::util::version_col(&cols) {
+			None => String::new(),
+			Some(ver) => format!(
+				"let Some(ver) = ::rusqlite::OptionalExtension::optional(conn.query_row(\"SELECT {} FROM {tab_name} WHERE {}\", ::rusqlite::params![{params}], |row| row.get(0)))? else {{ return Ok(false); }};",
+				ver.ident(),
+				crate::util::key_where(key)
+			),
+		};
+		let set_ver = match crate::util::version_col(&cols) {
+			None => String::new(),
+			Some(ver) => format!("row.{} = ver;", ver.field),
+		};
+		let mut f = gen_impl.generate_fn(format!("restore_by_{key_name}_sync"));
+		for col in key {
+			f = f.with_arg(&col.field, col.arg_typ());
+		}
+		f.with_arg("at", "f64")
+			.with_arg("conn", "&::rusqlite::Connection")
+			.with_return_type("Result<bool, ::rusqlite::Error>")
+			.make_pub()
+			.body(|fn_body| {
+				fn_body.push_parsed(format!(
+					"conn.execute_batch(\"SAVEPOINT {savepoint}\")?; let restored = (|| -> Result<bool, ::rusqlite::Error> {{ {ver} let Some(mut row) = Self::as_of_by_{key_name}_sync({params}, at, conn)? else {{ return Ok(false); }}; {set_ver} row.update_sync(conn) }})(); match restored {{ Ok(restored) => {{ conn.execute_batch(\"RELEASE {savepoint}\")?; Ok(restored) }} Err(err) => {{ conn.execute_batch(\"ROLLBACK TO {savepoint}; RELEASE {savepoint}\")?; Err(err) }} }}"
+				))?;
+				Ok(())
+			})?;
+	}
+
+	Ok(())
+}
@@ -1,6 +1,6 @@
 use virtue::{
 	parse::Attribute,
-	prelude::{AttributeAccess, Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
+	prelude::{Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
 };
 
 pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
@@ -8,7 +8,13 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
 		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_text(
+				&generator.target_name(),
+				"InsertSync",
+				"TextEnumSync",
+			));
+		}
 	};
 	generator.export_to_file("wb_sqlite", "InsertSync");
 	generator.finish()
@@ -16,91 +22,118 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 
 fn gen_struct(
 	generator: &mut Generator,
-	_attributes: Vec<Attribute>,
+	attributes: Vec<Attribute>,
 	fields: Option<Fields>,
 ) -> Result {
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let raw_name = crate::util::table_name(&generator.target_name().to_string(), &tab_attr);
+	let tab_name = crate::util::quote(&raw_name, tab_attr.quote_all);
+	let savepoint = crate::util::quote(&format!("{raw_name}_insert_many"), tab_attr.quote_all);
+	crate::util::integer_ranges(&struct_fields)?;
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+	let upsert = crate::util::upsert_clause(&tab_attr, &cols, &keys.pk)?;
+	let pk = keys
+		.single_pk()
+		.filter(|pk| pk.is_i64() && !pk.attr.is_readonly());
+	// the version column is not bound, a new row starts at version 0
+	let ver = crate::util::version_col(&cols).map_or(String::new(), |c| c.ident());
 
-	let mut pk = String::new();
-	let mut pk_typ = String::new();
-	let mut columns = String::new();
-	let mut columns_full = String::new();
-	let mut values = String::new();
-	let mut values_full = String::new();
-	for (ident, uf) in struct_fields {
-		let col_attr = uf
-			.attributes
-			.get_attribute::<crate::util::ColAttr>()?
-			.unwrap_or_default();
-		columns_full.push_str(&ident.to_string());
-		columns_full.push(',');
-		values_full.push_str("?,");
-		if col_attr.constraint.starts_with("PRIMARY KEY") {
-			pk = ident.to_string();
-			pk_typ = uf.type_string();
-		} else {
-			columns.push_str(&ident.to_string());
-			columns.push(',');
-			values.push_str("?,");
-		}
+	// readonly columns are filled by the db
+	let columns_full: Vec<&crate::util::Col> = cols
+		.iter()
+		.filter(|c| !(c.attr.version || c.attr.is_readonly()))
+		.collect();
+	let columns: Vec<&crate::util::Col> = columns_full
+		.iter()
+		.copied()
+		.filter(|c| pk.is_none_or(|pk| pk.field != c.field))
+		.collect();
+
+	fn stmt(
+		tab_name: &str,
+		columns: &[&crate::util::Col],
+		ver: &str,
+		upsert: Option<&crate::util::Sql>,
+	) -> String {
+		let sql = crate::util::insert_sql(tab_name, columns, ver, upsert);
+		format!("let mut stmt = conn.prepare_cached({})?;", sql.expr())
 	}
-	// get rid of the last ','
-	columns.pop();
-	columns_full.pop();
-	values.pop();
-	values_full.pop();
 
-	fn stmt(tab_name: &str, columns: &str, values: &str) -> String {
-		format!(
-			"let mut stmt = conn.prepare_cached(\"INSERT INTO {tab_name} ({columns}) VALUES ({values})\")?;"
-		)
+	fn insert(columns: &[&crate::util::Col], upsert: Option<&crate::util::Sql>) -> String {
+		let params = crate::util::params_sync(columns, &[]);
+		if upsert.is_none() {
+			format!("stmt.insert({params})?")
+		} else {
+			format!("stmt.query_row({params}, |row| row.get(0))?")
+		}
 	}
 
-	fn insert(columns: &str) -> String {
+	let body = |upsert: Option<&crate::util::Sql>| {
 		let mut s = String::new();
-		s.push_str("stmt.insert(::rusqlite::params![");
-		for c in columns.split(',') {
-			s.push_str(&format!("self.{c},"))
+		if let Some(pk) = pk {
+			s.push_str(&format!("let rowid = if self.{} > 0 {{", pk.field));
+			s.push_str(&stmt(&tab_name, &columns_full, &ver, upsert));
+			s.push_str(&insert(&columns_full, upsert));
+			s.push_str(" } else { ");
+			if columns.is_empty() && ver.is_empty() {
+				// the table has only the pk column
+				s.push_str(&format!(
+					"let mut stmt = conn.prepare_cached(\"INSERT INTO {tab_name} DEFAULT VALUES\")?;"
+				));
+				s.push_str("stmt.insert(())?");
+			} else {
+				s.push_str(&stmt(&tab_name, &columns, &ver, upsert));
+				s.push_str(&insert(&columns, upsert));
+			}
+			s.push_str(" }; Ok(rowid)");
+		} else {
+			s.push_str(&stmt(&tab_name, &columns_full, &ver, upsert));
+			s.push_str(&format!(
+				"let rowid = {}; Ok(rowid)",
+				insert(&columns_full, upsert)
+			));
 		}
-		s.pop(); // get rid of the last ','
-		s.push_str("])?");
 		s
-	}
+	};
 
 	if !columns_full.is_empty() {
-		generator
-			.generate_impl()
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
 			.generate_fn("insert_sync")
 			.with_self_arg(FnSelfArg::RefSelf)
 			.with_arg("conn", "&::rusqlite::Connection")
 			.with_return_type("Result<i64, ::rusqlite::Error>")
 			.make_pub()
 			.body(|fn_body| {
-				let mut s = String::new();
-				if pk.is_empty() || pk_typ != "i64" {
-					s.push_str(&stmt(&tab_name, &columns_full, &values_full));
-					s.push_str(&format!("let rowid = {}; Ok(rowid)", insert(&columns_full)));
-				} else {
-					s.push_str(&format!("let rowid = if self.{pk} > 0 {{"));
-					s.push_str(&stmt(&tab_name, &columns_full, &values_full));
-					s.push_str(&insert(&columns_full));
-					s.push_str(" } else { ");
-					if columns.is_empty() {
-						// the table has only the pk column
-						s.push_str(&format!(
-							"let mut stmt = conn.prepare_cached(\"INSERT INTO {tab_name} VALUES (NULL)\")?;"
-						));
-						s.push_str("stmt.insert(())?");
-					} else {
-						s.push_str(&stmt(&tab_name, &columns, &values));
-						s.push_str(&insert(&columns));
-					}
-					s.push_str(" }; Ok(rowid)");
-				}
-				fn_body.push_parsed(s)?;
+				fn_body.push_parsed(body(None))?;
+				Ok(())
+			})?;
+		if let Some(upsert) = &upsert {
+			gen_impl
+				.generate_fn("upsert_sync")
+				.with_self_arg(FnSelfArg::RefSelf)
+				.with_arg("conn", "&::rusqlite::Connection")
+				.with_return_type("Result<i64, ::rusqlite::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					fn_body.push_parsed(body(Some(upsert)))?;
+					Ok(())
+				})?;
+		}
+		gen_impl
+			.generate_fn("insert_many_sync")
+			.with_arg("items", "&[Self]")
+			.with_arg("conn", "&::rusqlite::Connection")
+			.with_return_type("Result<Vec<i64>, ::rusqlite::Error>")
+			.make_pub()
+			.body(|fn_body| {
+				fn_body.push_parsed(format!(
+					"conn.execute_batch(\"SAVEPOINT {savepoint}\")?; let mut rowids = Vec::with_capacity(items.len()); for item in items {{ match item.insert_sync(conn) {{ Ok(rowid) => rowids.push(rowid), Err(err) => {{ conn.execute_batch(\"ROLLBACK TO {savepoint}; RELEASE {savepoint}\")?; return Err(err); }} }} }} conn.execute_batch(\"RELEASE {savepoint}\")?; Ok(rowids)"
+				))?;
 				Ok(())
 			})?;
 	}
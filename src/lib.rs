@@ -1,13 +1,26 @@
 #![cfg_attr(doc, doc = include_str!("../README.md"))]
 
+mod columns;
 mod create_index;
 mod create_table;
 mod create_table_log;
+mod delete;
+mod delete_sync;
+mod flatten;
+mod flatten_sql;
+mod flatten_sync;
+mod from_row_sync;
 mod get;
+mod get_sync;
+mod history;
+mod history_sync;
 mod insert;
 mod insert_sync;
 mod select;
 mod select_as;
+mod sql_type;
+mod text_enum;
+mod text_enum_sync;
 mod update;
 mod update_sync;
 mod util;
@@ -16,8 +29,12 @@ use virtue::prelude::TokenStream;
 
 /// const CREATE_INDEX_SQL: &'static str = "CREATE INDEX ..."
 ///
-/// Create index for every column with a [foreign-key-clause](https://www.sqlite.org/syntax/foreign-key-clause.html).\
-/// Works only if constraint is in all caps, lowercase serves as escape hatch.
+/// Create index for every column with a [foreign-key-clause](https://www.sqlite.org/syntax/foreign-key-clause.html)
+/// and for the index attributes.\
+/// Works only if constraint is in all caps, lowercase serves as escape hatch.\
+/// The field attribute `references` always creates the index.
+///
+/// const DROP_INDEX_SQL drops these indexes.
 ///
 /// Possible breaking change from 0.2.1 to 0.3.0: \
 /// Removed the IF NOT EXISTS part.
@@ -45,6 +62,47 @@ use virtue::prelude::TokenStream;
 ///    "CREATE INDEX cat_owner_idx ON cat(owner); "
 ///    )
 /// );
+/// assert_eq!(
+///    Cat::DROP_INDEX_SQL,
+///    "DROP INDEX cat_mother_idx; DROP INDEX cat_owner_idx; "
+/// );
+/// ```
+///
+/// ## Index attributes
+///
+/// The field attribute `index` creates the index `{tab_name}_{col_name}_idx`,
+/// `index = "name"` names it, fields with the same name make one multi-column index in field order.\
+/// The struct attribute `index(cols = "a,b", unique, where = "expr", name = "name")` can be repeated.
+/// `cols` are field-names, column-names or expressions like `lower(email)`.
+/// The default name is `{tab_name}_{cols}_idx` with the non-identifier characters of `cols` replaced.
+///
+/// ```rust
+/// # use wb_sqlite::CreateIndexSql;
+/// #[derive(CreateIndexSql)]
+/// #[sql(index(cols = "lower(email)", unique, where = "deleted_at IS NULL"))]
+/// #[sql(index(cols = "city, zip", name = "person_place"))]
+/// struct Person {
+///    #[sql(pk)]
+///    id: i64,
+///    #[sql(index = "person_full_name")]
+///    last_name: String,
+///    #[sql(index = "person_full_name")]
+///    first_name: String,
+///    #[sql(index)]
+///    email: String,
+///    city: String,
+///    zip: String,
+///    deleted_at: Option<f64>,
+/// }
+/// assert_eq!(
+///    Person::CREATE_INDEX_SQL,
+///    concat!(
+///    "CREATE INDEX person_full_name ON person(last_name,first_name); ",
+///    "CREATE INDEX person_email_idx ON person(email); ",
+///    "CREATE UNIQUE INDEX person_lower_email_idx ON person(lower(email)) WHERE deleted_at IS NULL; ",
+///    "CREATE INDEX person_place ON person(city,zip); "
+///    )
+/// );
 /// ```
 #[proc_macro_derive(CreateIndexSql, attributes(sql))]
 pub fn create_index(input: TokenStream) -> TokenStream {
@@ -56,6 +114,8 @@ pub fn create_index(input: TokenStream) -> TokenStream {
 /// `"CREATE TABLE {tab_name} ({col_defs}{tab_constraint}) STRICT{tab_option};"`\
 /// `col_defs = {field_name} {col_typ} {col_constraint},`
 ///
+/// const DROP_TABLE_SQL: `"DROP TABLE {tab_name};"`
+///
 /// Possible breaking change from 0.2.1 to 0.3.0: \
 /// Removed the IF NOT EXISTS part.
 /// This part made failures silent.
@@ -75,6 +135,192 @@ pub fn create_index(input: TokenStream) -> TokenStream {
 /// constraint = "[column constraint](https://www.sqlite.org/syntax/column-constraint.html)"
 /// )]
 ///
+/// Structured column constraints, rendered before the constraint str:
+///
+/// #[sql(
+/// pk, unique,
+/// check = "expr",
+/// default = "value",
+/// references = "foreign_table(column)",
+/// on_delete = "CASCADE | SET NULL | SET DEFAULT | RESTRICT | NO ACTION"
+/// )]
+///
+/// `pk`, `unique` and `references` are used by the other derives,
+/// the same as a constraint str starting with PRIMARY KEY, UNIQUE or REFERENCES.\
+/// `skip_get` prevents the get_by fn of a PRIMARY KEY or UNIQUE column, see [Get].
+///
+/// ```rust
+/// # use wb_sqlite::CreateTableSql;
+/// #[derive(CreateTableSql)]
+/// struct Cat {
+///    #[sql(pk)]
+///    id: i64,
+///    #[sql(unique, check = "length(name) > 1")]
+///    name: String,
+///    #[sql(references = "human(id)", on_delete = "SET NULL")]
+///    owner: Option<i64>,
+///    #[sql(unchecked, default = "9", constraint = "CHECK (lives <= 9)")]
+///    lives: u8,
+/// }
+/// assert_eq!(
+///    Cat::CREATE_TABLE_SQL,
+///    concat!(
+///    "CREATE TABLE cat (id INTEGER NOT NULL PRIMARY KEY, ",
+///    "name TEXT NOT NULL UNIQUE CHECK (length(name) > 1), ",
+///    "owner INTEGER REFERENCES human(id) ON DELETE SET NULL, ",
+///    "lives INTEGER NOT NULL DEFAULT 9 CHECK (lives <= 9)) STRICT;"
+///    )
+/// );
+/// assert_eq!(Cat::DROP_TABLE_SQL, "DROP TABLE cat;");
+/// ```
+///
+/// bool and the integer types narrower than i64 get a CHECK of their rust domain,
+/// `CHECK (ok IN (0,1))`, `CHECK (pos BETWEEN 0 AND 255)` for u8,
+/// because SQLite stores any i64 and the read into the rust type would fail.
+/// `unchecked` leaves it out, also a `typ` override.
+///
+/// ```rust
+/// # use wb_sqlite::CreateTableSql;
+/// #[derive(CreateTableSql)]
+/// struct Dice {
+///    eyes: u8,
+///    fair: Option<bool>,
+///    #[sql(unchecked)]
+///    throws: u32,
+/// }
+/// assert_eq!(
+///    Dice::CREATE_TABLE_SQL,
+///    concat!(
+///    "CREATE TABLE dice (eyes INTEGER NOT NULL CHECK (eyes BETWEEN 0 AND 255), ",
+///    "fair INTEGER CHECK (fair IN (0,1)), throws INTEGER NOT NULL) STRICT;"
+///    )
+/// );
+/// ```
+///
+/// #[sql(version)] marks the optimistic locking column, see [Update].
+/// Its col_defs are `{field_name} INTEGER NOT NULL DEFAULT 0`.
+///
+/// ## Skip, readonly and generated fields
+///
+/// #[sql(skip)] leaves the field out of all SQL, e.g. a cache or PhantomData field.
+/// The rusqlite row mappers [FromRowSync] and [GetSync] fill it with `Default::default()`,
+/// for sqlx::FromRow add `#[sqlx(skip)]`.
+///
+/// #[sql(readonly)] keeps the column in CREATE TABLE and SELECT,
+/// but it is not bound in INSERT / UPDATE, the db fills it in,
+/// e.g. a DEFAULT CURRENT_TIMESTAMP or generated column.
+/// There is no update_{field-name} fn and update_columns returns an error for it.
+///
+/// #[sql(generated = "expr", stored)] renders `GENERATED ALWAYS AS (expr) STORED`,
+/// without `stored` the generated column is VIRTUAL.
+/// A generated column is readonly.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,InsertSync};
+/// #[derive(CreateTableSql,InsertSync)]
+/// struct Post {
+///    #[sql(pk)]
+///    id: i64,
+///    text: String,
+///    #[sql(readonly, default = "CURRENT_TIMESTAMP")]
+///    created: String,
+///    #[sql(generated = "length(text)", stored)]
+///    len: i64,
+///    #[sql(skip)]
+///    words: usize,
+/// }
+/// assert_eq!(
+///    Post::CREATE_TABLE_SQL,
+///    concat!(
+///    "CREATE TABLE post (id INTEGER NOT NULL PRIMARY KEY, text TEXT NOT NULL, ",
+///    "created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP, ",
+///    "len INTEGER NOT NULL GENERATED ALWAYS AS (length(text)) STORED) STRICT;"
+///    )
+/// );
+/// ```
+///
+/// ## Flatten
+///
+/// #[sql(flatten)] stores the fields of a nested struct as columns `{field-name}_{nested-column}`,
+/// #[sql(flatten, prefix = "p_")] sets another prefix, an empty prefix keeps the nested column names.
+/// The nested struct derives [FlattenSql] and [Flatten] / [FlattenSync].
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,FlattenSql};
+/// #[derive(FlattenSql)]
+/// struct Address {
+///    street: String,
+///    #[sql(default = "'Vienna'")]
+///    city: String,
+/// }
+/// #[derive(CreateTableSql)]
+/// struct Shop {
+///    #[sql(pk)]
+///    id: i64,
+///    #[sql(flatten)]
+///    address: Address,
+///    #[sql(flatten, prefix = "bill_")]
+///    billing: Address,
+/// }
+/// assert_eq!(
+///    Shop::CREATE_TABLE_SQL,
+///    concat!(
+///    "CREATE TABLE shop (id INTEGER NOT NULL PRIMARY KEY, ",
+///    "address_street TEXT NOT NULL, address_city TEXT NOT NULL DEFAULT 'Vienna', ",
+///    "bill_street TEXT NOT NULL, bill_city TEXT NOT NULL DEFAULT 'Vienna') STRICT;"
+///    )
+/// );
+/// ```
+///
+/// ## Table and column names
+///
+/// #[sql(name = "table name", rename_all = "lowercase | UPPERCASE | PascalCase | camelCase | snake_case | SCREAMING_SNAKE_CASE")]
+///
+/// #[sql(name = "column name")]
+///
+/// The struct attribute `name` overrides the table name,
+/// `rename_all` converts the field-names to column names,
+/// the field attribute `name` overrides the column name.\
+/// All derives use these names, SELECT aliases a renamed column to the field-name.
+/// The struct attributes can be split over several `#[sql(...)]`.
+///
+/// Names that are SQLite keywords or no plain identifiers are double-quoted,
+/// the raw identifier prefix `r#` of a field-name is stripped.
+/// The struct attribute `quote_all` double-quotes all table and column names.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,SelectSql};
+/// #[derive(CreateTableSql,SelectSql)]
+/// struct Order {
+///    group: String,
+///    r#type: String,
+/// }
+/// assert_eq!(
+///    Order::CREATE_TABLE_SQL,
+///    "CREATE TABLE \"order\" (\"group\" TEXT NOT NULL, type TEXT NOT NULL) STRICT;"
+/// );
+/// assert_eq!(Order::SELECT_SQL, "SELECT \"group\",type FROM \"order\"");
+/// ```
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,SelectSql};
+/// #[derive(CreateTableSql,SelectSql)]
+/// #[sql(name = "tblCustomer", rename_all = "PascalCase")]
+/// struct Customer {
+///    #[sql(pk, name = "CustomerID")]
+///    id: i64,
+///    full_name: String,
+/// }
+/// assert_eq!(
+///    Customer::CREATE_TABLE_SQL,
+///    "CREATE TABLE tblCustomer (CustomerID INTEGER NOT NULL PRIMARY KEY, FullName TEXT NOT NULL) STRICT;"
+/// );
+/// assert_eq!(
+///    Customer::SELECT_SQL,
+///    "SELECT CustomerID AS id,FullName AS full_name FROM tblCustomer"
+/// );
+/// ```
+///
 /// ## Table Name creation: PascalCase with digits as lowercase to snake_case
 ///
 /// ```rust
@@ -164,25 +410,249 @@ pub fn create_index(input: TokenStream) -> TokenStream {
 /// # assert!(rusqlite::Connection::open_in_memory().unwrap().execute_batch(WineBottle::CREATE_TABLE_SQL).is_ok());
 /// ```
 ///
+/// ## Enum
+///
+/// A fieldless enum becomes a lookup table with one row per variant name.\
+/// Reference it from a struct column with `typ = "TEXT"` and `REFERENCES {tab_name}(name)`,
+/// derive [TextEnum] or [TextEnumSync] to bind and read the column.
+/// The table name follows the struct attributes `name` and `quote_all`.
+///
+/// ```rust
+/// # use wb_sqlite::CreateTableSql;
+/// #[derive(CreateTableSql)]
+/// enum Color {
+///    Red,
+///    Green,
+/// }
+/// assert_eq!(
+/// Color::CREATE_TABLE_SQL,
+/// concat!(
+/// "CREATE TABLE color (name TEXT NOT NULL PRIMARY KEY) STRICT; ",
+/// "INSERT INTO color (name) VALUES ('Red'),('Green');",
+/// ));
+/// # assert!(rusqlite::Connection::open_in_memory().unwrap().execute_batch(Color::CREATE_TABLE_SQL).is_ok());
+/// ```
+///
+/// Enums with data-carrying variants or without variants are a compile error.
+///
+/// ```compile_fail
+/// # use wb_sqlite::CreateTableSql;
+/// #[derive(CreateTableSql)]
+/// enum Shape {
+///    Circle(f64),
+///    Square { side: f64 },
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use wb_sqlite::CreateTableSql;
+/// #[derive(CreateTableSql)]
+/// enum Never {}
+/// ```
+///
 /// ## SQLite / Rust type mapping
 ///
+/// u64, usize, i128 and u128 are a compile error, their values can exceed i64::MAX.
+/// Use i64, or set `typ` to store them anyway: sqlx and rusqlite fail on a larger value.\
+/// Only CreateTableSql, CreateTableLogSql, FlattenSql, Insert and Update with the sync variants
+/// check it, reading such a column with Get or Select is fine.
+///
+/// ```compile_fail
+/// # use wb_sqlite::CreateTableSql;
+/// #[derive(CreateTableSql)]
+/// struct Counter {
+///    hits: u64,
+/// }
+/// ```
+///
+/// ```rust
+/// # use wb_sqlite::SelectSql;
+/// #[derive(SelectSql)]
+/// struct Counter {
+///    hits: u64,
+/// }
+/// assert_eq!(Counter::SELECT_SQL, "SELECT hits FROM counter");
+/// ```
+///
 /// `INTEGER NOT NULL = bool, u8, u16, u32, i8, i16, i32, i64`\
 /// `REAL NOT NULL    = f32, f64`\
-/// `TEXT NOT NULL    = &str, String`\
-/// `BLOB NOT NULL    = &[u8], Vec<u8>`\
+/// `TEXT NOT NULL    = &str, String, Box<str>, Rc<str>, Arc<str>, Cow<str>`\
+/// `BLOB NOT NULL    = &[u8], Vec<u8>, Box<[u8]>, Cow<[u8]>`\
 /// `INTEGER          = Option<bool>, Option<u8>, Option<u16> ... Option<i64>`\
 /// `REAL             = Option<f32>, Option<f64>`\
-/// `TEXT             = Option<String>`\
-/// `BLOB             = Option<Vec<u8>>`\
+/// `TEXT             = Option<String>, Option<&str> ...`\
+/// `BLOB             = Option<Vec<u8>>, Option<&[u8]> ...`\
 /// `ANY              = all other`
+///
+/// Paths and lifetimes are ignored: `std::primitive::i64`, `Option<&'a str>`.\
+/// The macro only sees the type tokens, type aliases are ANY, set their datatype with `typ`.\
+/// Newtypes and fieldless enums get their datatype with derive [SqlType].
+///
+/// With cargo features, in the storage format of sqlx and rusqlite with the same feature:
+///
+/// `uuid       BLOB = Uuid`\
+/// `chrono     TEXT = NaiveDate, NaiveDateTime, NaiveTime, DateTime<Tz>`\
+/// `time       TEXT = OffsetDateTime, PrimitiveDateTime, time::Date, time::Time`\
+/// `serde_json TEXT = serde_json::Value, JsonValue, Json<T>` with `CHECK (json_valid({column}))`
+///
+/// Date, Time and Value need the crate path, other types with these names stay ANY.\
+/// Dates and times are ISO-8601 TEXT only, sqlx and rusqlite bind them as TEXT,
+/// a STRICT INTEGER column would reject them. For unix time use an i64 field.
+///
+/// `rust_decimal TEXT = Decimal`, the exact decimal string.
+/// sqlx and rusqlite can not bind Decimal with SQLite,
+/// bind `to_string()` and read with `parse()` in your own queries.
 #[proc_macro_derive(CreateTableSql, attributes(sql))]
 pub fn create_table(input: TokenStream) -> TokenStream {
 	create_table::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
 }
 
+/// impl `sqlx::Type`, `sqlx::Encode` and `sqlx::Decode` for a fieldless enum
+///
+/// The variant name is stored as TEXT, decoding an unknown name is an error.\
+/// All sqlx traits of an enum column come from this derive, [Get] and [Insert] do not support enums.
+/// [TextEnumSync] is the rusqlite variant, derive both to use the enum with both crates.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,Get,Insert,TextEnum};
+/// #[derive(Debug,PartialEq,CreateTableSql,TextEnum)]
+/// enum Mood {
+///    Happy,
+///    Grumpy,
+/// }
+/// #[derive(CreateTableSql,Get,Insert,sqlx::FromRow)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    #[sql(typ = "TEXT", constraint = "NOT NULL REFERENCES mood(name)")]
+///    mood: Mood,
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(Mood::CREATE_TABLE_SQL).await?;
+///    conn.execute(Cat::CREATE_TABLE_SQL).await?;
+///
+///    let id = Cat { id: 0, mood: Mood::Grumpy }.insert(&mut conn).await?;
+///    let c = Cat::get_by_id(id, &mut conn).await?;
+///    assert_eq!(c.mood, Mood::Grumpy);
+///
+///    Ok(())
+/// }
+/// ```
+///
+/// A struct, or an enum with data-carrying variants, is a compile error.
+///
+/// ```rust,compile_fail
+/// # use wb_sqlite::TextEnum;
+/// #[derive(TextEnum)]
+/// struct Mood(String);
+/// ```
+#[proc_macro_derive(TextEnum)]
+pub fn text_enum(input: TokenStream) -> TokenStream {
+	text_enum::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// impl `rusqlite::ToSql` and `rusqlite::types::FromSql` for a fieldless enum
+///
+/// The variant name is stored as TEXT, reading an unknown name is an error.\
+/// All rusqlite traits of an enum column come from this derive,
+/// [GetSync] and [InsertSync] do not support enums. See [TextEnum] for sqlx.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,GetSync,InsertSync,TextEnumSync};
+/// #[derive(Debug,PartialEq,CreateTableSql,TextEnumSync)]
+/// enum Mood {
+///    Happy,
+///    Grumpy,
+/// }
+/// #[derive(CreateTableSql,GetSync,InsertSync)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    #[sql(typ = "TEXT", constraint = "NOT NULL REFERENCES mood(name)")]
+///    mood: Mood,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Mood::CREATE_TABLE_SQL)?;
+///    conn.execute_batch(Cat::CREATE_TABLE_SQL)?;
+///
+///    let id = Cat { id: 0, mood: Mood::Happy }.insert_sync(&conn)?;
+///    let c = Cat::get_by_id_sync(id, &conn)?;
+///    assert_eq!(c.mood, Mood::Happy);
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(TextEnumSync)]
+pub fn text_enum_sync(input: TokenStream) -> TokenStream {
+	text_enum_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// `const SQL_TYPE: &'static str`\
+/// `const NULLABLE: bool`
+///
+/// SQLite datatype of a newtype or a fieldless enum, for the columns of this type.\
+/// [CreateTableSql], [CreateTableLogSql] and [FlattenSql] read the consts of a type they do not know,
+/// a type without them stays ANY.
+///
+/// A newtype has the datatype of its field, or of the field attribute `typ`.
+/// `Option<T>` is NULLABLE, other types add NOT NULL to the column.
+/// The range CHECK of the field type is left out.\
+/// A fieldless enum is TEXT, its variant name.
+///
+/// Only local types can derive SqlType, the consts are inherent.
+/// There is no SqlType trait for std or foreign types: a proc-macro crate can not export a trait.
+/// Type aliases are not resolved, the macro only sees the type tokens.
+/// Set the datatype of these columns with the field attribute `typ`.\
+/// A struct with type or const generic parameters reads no consts, its unknown types are ANY.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,SqlType};
+/// #[derive(SqlType)]
+/// struct CatId(i64);
+/// #[derive(SqlType)]
+/// struct Tag(Option<String>);
+/// #[derive(SqlType)]
+/// enum Color {
+///    Black,
+///    White,
+/// }
+/// #[derive(CreateTableSql)]
+/// struct Cat {
+///    id: CatId,
+///    mother: Option<CatId>,
+///    tag: Tag,
+///    color: Color,
+/// }
+/// assert_eq!(CatId::SQL_TYPE, "INTEGER");
+/// assert_eq!(
+///    Cat::CREATE_TABLE_SQL,
+///    "CREATE TABLE cat (id INTEGER NOT NULL, mother INTEGER, tag TEXT, color TEXT NOT NULL) STRICT;"
+/// );
+/// ```
+///
+/// A struct with more than one field is a compile error.
+///
+/// ```rust,compile_fail
+/// # use wb_sqlite::SqlType;
+/// #[derive(SqlType)]
+/// struct Point(i64, i64);
+/// ```
+#[proc_macro_derive(SqlType, attributes(sql))]
+pub fn sql_type(input: TokenStream) -> TokenStream {
+	sql_type::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
 /// const CREATE_TABLE_LOG_SQL: &'static str = "CREATE ..."
 ///
 /// Create logging-table + trigger to log all table row modifications to the logging-table.
+/// The logging-table has an index on the PRIMARY KEY columns, also of a table constraint `PRIMARY KEY(a,b)`.
+///
+/// const DROP_TABLE_LOG_SQL drops the trigger, index and logging-table,
+/// run it before DROP_TABLE_SQL, the triggers are gone with the table.
 ///
 /// ```rust
 /// # use wb_sqlite::CreateTableLogSql;
@@ -203,6 +673,78 @@ pub fn create_table(input: TokenStream) -> TokenStream {
 ///    "BEGIN INSERT INTO favorite_pet_log (id,name) VALUES (OLD.id,OLD.name); END;"
 ///    )
 /// );
+/// assert_eq!(
+///    FavoritePet::DROP_TABLE_LOG_SQL,
+///    concat!(
+///    "DROP TRIGGER favorite_pet_update; DROP TRIGGER favorite_pet_delete; ",
+///    "DROP INDEX favorite_pet_log_id_idx; DROP TABLE favorite_pet_log;"
+///    )
+/// );
+/// ```
+///
+/// ## Audit
+///
+/// #[sql(log_audit)] adds the columns `log_id INTEGER PRIMARY KEY AUTOINCREMENT`,
+/// `log_op` with 'I', 'U' or 'D' and `log_at` in unix seconds with fraction (needs SQLite 3.42),
+/// #[sql(log_insert)] adds an INSERT trigger which logs the NEW row.\
+/// AUTOINCREMENT keeps the ids of pruned log rows from being used again.
+///
+/// ```rust
+/// # use wb_sqlite::CreateTableLogSql;
+/// #[derive(CreateTableLogSql)]
+/// #[sql(log_audit, log_insert)]
+/// struct Stock {
+///    #[sql(pk)]
+///    id: i64,
+///    qty: i32,
+/// }
+/// assert_eq!(
+///    Stock::CREATE_TABLE_LOG_SQL,
+///    concat!(
+///    "CREATE TABLE stock_log (log_id INTEGER PRIMARY KEY AUTOINCREMENT, ",
+///    "log_op TEXT NOT NULL CHECK (log_op IN ('I','U','D')), ",
+///    "log_at REAL NOT NULL DEFAULT (unixepoch('subsec')), ",
+///    "id INTEGER NOT NULL, qty INTEGER NOT NULL) STRICT; ",
+///    "CREATE INDEX stock_log_id_idx ON stock_log(id); ",
+///    "CREATE TRIGGER stock_insert AFTER INSERT ON stock ",
+///    "BEGIN INSERT INTO stock_log (log_op,id,qty) VALUES ('I',NEW.id,NEW.qty); END; ",
+///    "CREATE TRIGGER stock_update UPDATE ON stock ",
+///    "BEGIN INSERT INTO stock_log (log_op,id,qty) VALUES ('U',OLD.id,OLD.qty); END; ",
+///    "CREATE TRIGGER stock_delete DELETE ON stock ",
+///    "BEGIN INSERT INTO stock_log (log_op,id,qty) VALUES ('D',OLD.id,OLD.qty); END;"
+///    )
+/// );
+/// ```
+///
+/// ## Changed columns
+///
+/// #[sql(log_changed)] logs an UPDATE only if a column changed: `UPDATE ON {tab_name} WHEN OLD.a IS NOT NEW.a OR ...`,
+/// the version column is not compared.\
+/// #[sql(log_sparse)] also stores NULL for the unchanged columns of an UPDATE, PRIMARY KEY columns are always stored.
+/// The column `log_mask INTEGER NOT NULL` has bit i set if column i changed, INSERT and DELETE set all bits.
+/// A sparse log does not fit [History] and supports no flatten fields.
+///
+/// ```rust
+/// # use wb_sqlite::CreateTableLogSql;
+/// #[derive(CreateTableLogSql)]
+/// #[sql(log_sparse)]
+/// struct Stock {
+///    #[sql(pk)]
+///    id: i64,
+///    qty: i32,
+/// }
+/// assert_eq!(
+///    Stock::CREATE_TABLE_LOG_SQL,
+///    concat!(
+///    "CREATE TABLE stock_log (log_mask INTEGER NOT NULL, id INTEGER NOT NULL, qty INTEGER) STRICT; ",
+///    "CREATE INDEX stock_log_id_idx ON stock_log(id); ",
+///    "CREATE TRIGGER stock_update UPDATE ON stock WHEN OLD.id IS NOT NEW.id OR OLD.qty IS NOT NEW.qty ",
+///    "BEGIN INSERT INTO stock_log (log_mask,id,qty) VALUES ((OLD.id IS NOT NEW.id)+(OLD.qty IS NOT NEW.qty)*2,",
+///    "OLD.id,CASE WHEN OLD.qty IS NOT NEW.qty THEN OLD.qty END); END; ",
+///    "CREATE TRIGGER stock_delete DELETE ON stock ",
+///    "BEGIN INSERT INTO stock_log (log_mask,id,qty) VALUES (3,OLD.id,OLD.qty); END;"
+///    )
+/// );
 /// ```
 #[proc_macro_derive(CreateTableLogSql, attributes(sql))]
 pub fn create_table_log(input: TokenStream) -> TokenStream {
@@ -213,6 +755,9 @@ pub fn create_table_log(input: TokenStream) -> TokenStream {
 ///
 /// "SELECT {columns} FROM {tab_name}"
 ///
+/// A renamed column is aliased to the field-name: `{col_name} AS {field-name}`,
+/// so `sqlx::FromRow` and `from_row_by_name_sync` work without extra attributes.
+///
 /// ```rust
 /// # use wb_sqlite::SelectSql;
 /// #[derive(SelectSql)]
@@ -242,13 +787,13 @@ pub fn select(input: TokenStream) -> TokenStream {
 ///
 /// #[sqlas(from = "[select stmt after from](https://www.sqlite.org/lang_select.html)")]
 ///
-/// if omitted defaults to {tab_name}
+/// if omitted defaults to {tab_name}, see the struct attribute `name` of [CreateTableSql]
 ///
 /// ## Field attributes
 ///
 /// #[sqlas(col = "result column")]
 ///
-/// if ommited defaults to {col_name}
+/// if ommited defaults to {col_name}, aliased to the field-name if renamed
 ///
 /// ```rust
 /// # use wb_sqlite::SelectAsSql;
@@ -273,15 +818,60 @@ pub fn select(input: TokenStream) -> TokenStream {
 ///    )
 /// );
 /// ```
-#[proc_macro_derive(SelectAsSql, attributes(sqlas))]
+#[proc_macro_derive(SelectAsSql, attributes(sql, sqlas))]
 pub fn select_as(input: TokenStream) -> TokenStream {
 	select_as::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
 }
 
+/// `fn from_row_sync(row: &rusqlite::Row) -> rusqlite::Result<Self>`
+///
+/// `fn from_row_by_name_sync(row: &rusqlite::Row) -> rusqlite::Result<Self>`
+///
+/// Generate rusqlite row mapper.\
+/// `from_row_sync` maps by column index in the column order of [SelectSql],\
+/// `from_row_by_name_sync` maps by column name, e.g. the aliases of [SelectAsSql].
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,FromRowSync,InsertSync,SelectAsSql,SelectSql};
+/// #[derive(CreateTableSql,FromRowSync,InsertSync,SelectSql,SelectAsSql)]
+/// #[sqlas(from = "cat")]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    #[sqlas(col = "upper(name)")]
+///    name: String,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Cat::CREATE_TABLE_SQL)?;
+///    Cat { id: 0, name: "miau".to_owned() }.insert_sync(&conn)?;
+///
+///    let mut stmt = conn.prepare(Cat::SELECT_SQL)?;
+///    let cats = stmt
+///       .query_map([], Cat::from_row_sync)?
+///       .collect::<Result<Vec<_>, _>>()?;
+///    assert_eq!(cats[0].name, "miau");
+///
+///    let mut stmt = conn.prepare(Cat::SELECT_AS_SQL)?;
+///    let cats = stmt
+///       .query_map([], Cat::from_row_by_name_sync)?
+///       .collect::<Result<Vec<_>, _>>()?;
+///    assert_eq!(cats[0].name, "MIAU");
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(FromRowSync, attributes(sql))]
+pub fn from_row_sync(input: TokenStream) -> TokenStream {
+	from_row_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
 /// fn get_by_{field-name}({field-name}: {field-type}, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<Self, sqlx::Error>
 ///
 /// Generate fn for PRIMARY KEY and every UNIQUE constraint.\
-/// Works only if constraint is in all caps, lowercase serves as escape hatch.
+/// Works only if constraint is in all caps, lowercase serves as escape hatch.\
+/// With the field attributes `pk` and `unique` there is no need for the constraint str,
+/// the field attribute `skip_get` prevents the fn.
 ///
 /// ```rust
 /// # use wb_sqlite::{Get,CreateTableSql,Insert};
@@ -319,18 +909,195 @@ pub fn select_as(input: TokenStream) -> TokenStream {
 ///    Ok(())
 /// }
 /// ```
-#[proc_macro_derive(Get, attributes(sql))]
-pub fn get(input: TokenStream) -> TokenStream {
-	get::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
-}
-
-/// fn insert(&self, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<i64, sqlx::Error>
 ///
-/// Generate fn for INSERT with sqlx.
+/// ## Composite keys
 ///
-/// If there is a PRIMARY KEY and the rust-type is i64 then the insert depends on the value of the pk.\
-/// If pk > 0 then do a full insert including the pk, else do a insert without the pk so sqlite assigns a new one,
-/// which is returned as result.
+/// The table constraints PRIMARY KEY(a,b) and UNIQUE(a,b) generate `get_by_a_and_b(a, b, exec)`.
+///
+/// ```rust
+/// # use wb_sqlite::{Get,CreateTableSql,Insert};
+/// #[derive(CreateTableSql,Get,Insert,sqlx::FromRow)]
+/// #[sql(constraint = "PRIMARY KEY(cat,toy)")]
+/// struct CatToy {
+///    cat: i64,
+///    toy: i64,
+///    fun: u8,
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(CatToy::CREATE_TABLE_SQL).await?;
+///
+///    let ct = CatToy { cat: 1, toy: 2, fun: 9 };
+///    ct.insert(&mut conn).await?;
+///    let ct2 = CatToy::get_by_cat_and_toy(1, 2, &mut conn).await?;
+///    assert_eq!(ct2.fun, 9);
+///
+///    Ok(())
+/// }
+/// ```
+///
+/// ## Enum
+///
+/// An enum is a compile error, a fieldless enum column needs derive [TextEnum].
+#[proc_macro_derive(Get, attributes(sql))]
+pub fn get(input: TokenStream) -> TokenStream {
+	get::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// fn get_by_{field-name}_sync({field-name}: {field-type}, conn: &rusqlite::Connection) -> Result<Self, rusqlite::Error>
+///
+/// Generate fn for PRIMARY KEY and every UNIQUE constraint.\
+/// Works only if constraint is in all caps, lowercase serves as escape hatch.\
+/// The field attribute `skip_get` prevents the fn, see [Get].
+///
+/// The table constraints PRIMARY KEY(a,b) and UNIQUE(a,b) generate `get_by_a_and_b_sync(a, b, conn)`.
+///
+/// The row is mapped by column index, no extra trait is required.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,GetSync,InsertSync};
+/// #[derive(CreateTableSql,GetSync,InsertSync)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    #[sql(constraint = "UNIQUE")]
+///    name: String,
+///    owner: String,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Cat::CREATE_TABLE_SQL)?;
+///
+///    let c = Cat {
+///       id: 0,
+///       name: "meouw".to_owned(),
+///       owner: "nobody".to_owned()
+///    };
+///    let id = c.insert_sync(&conn)?;
+///    assert!(id > 0);
+///
+///    let c2 = Cat::get_by_id_sync(1,&conn)?;
+///    assert_eq!(c2.name,"meouw");
+///    let c3 = Cat::get_by_name_sync("meouw",&conn)?;
+///    assert_eq!(c3.owner,"nobody");
+///
+///    Ok(())
+/// }
+/// ```
+///
+/// ## Enum
+///
+/// An enum is a compile error, a fieldless enum column needs derive [TextEnumSync].
+#[proc_macro_derive(GetSync, attributes(sql))]
+pub fn get_sync(input: TokenStream) -> TokenStream {
+	get_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// `fn history_by_{pk}({pk}: {pk-type}, exec: impl sqlx::SqliteExecutor<'_>) -> Result<Vec<Self>, sqlx::Error>`\
+/// `fn as_of_by_{pk}({pk}: {pk-type}, at: f64, exec) -> Result<Option<Self>, sqlx::Error>`\
+/// `fn restore_by_{pk}({pk}: {pk-type}, at: f64, conn: impl sqlx::Acquire) -> Result<bool, sqlx::Error>`
+///
+/// Read the log table of [CreateTableLogSql].\
+/// history_by returns the logged rows of the PRIMARY KEY, oldest first.
+///
+/// With the struct attribute `log_audit`, as_of_by returns the row state at the unix time `at`,
+/// None if there was no row. A change at the same millisecond as `at` is already in the state.
+/// Only the struct attribute `log_insert` logs when a row was inserted,
+/// without it a row inserted after `at` and unchanged since is returned in its current state.
+/// The struct attribute `restore` adds restore_by, which writes the state at `at` back with update
+/// in a transaction, it needs derive [Update]. It returns false if there is no state or the row is deleted.
+/// The version column keeps its current value, so the restore is not a stale update.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableLogSql,CreateTableSql,History,Insert,Update};
+/// #[derive(CreateTableSql,CreateTableLogSql,History,Insert,Update,sqlx::FromRow)]
+/// #[sql(log_audit, restore)]
+/// struct Cat {
+///    #[sql(pk)]
+///    id: i64,
+///    name: String,
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(Cat::CREATE_TABLE_SQL).await?;
+///    conn.execute(Cat::CREATE_TABLE_LOG_SQL).await?;
+///
+///    let mut c = Cat { id: 0, name: "Tom".to_owned() };
+///    c.id = c.insert(&mut conn).await?;
+///    let (at,): (f64,) = sqlx::query_as("SELECT unixepoch('subsec')").fetch_one(&mut conn).await?;
+///    // log_at has millisecond precision
+///    std::thread::sleep(std::time::Duration::from_millis(5));
+///    c.name = "Garfield".to_owned();
+///    c.update(&mut conn).await?;
+///
+///    let history = Cat::history_by_id(c.id, &mut conn).await?;
+///    assert_eq!(history[0].name, "Tom");
+///    let old = Cat::as_of_by_id(c.id, at, &mut conn).await?;
+///    assert_eq!(old.map(|c| c.name).as_deref(), Some("Tom"));
+///    assert!(Cat::restore_by_id(c.id, at, &mut conn).await?);
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(History, attributes(sql))]
+pub fn history(input: TokenStream) -> TokenStream {
+	history::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// `fn history_by_{pk}_sync({pk}: {pk-type}, conn: &rusqlite::Connection) -> Result<Vec<Self>, rusqlite::Error>`\
+/// `fn as_of_by_{pk}_sync({pk}: {pk-type}, at: f64, conn) -> Result<Option<Self>, rusqlite::Error>`\
+/// `fn restore_by_{pk}_sync({pk}: {pk-type}, at: f64, conn) -> Result<bool, rusqlite::Error>`
+///
+/// [History] with rusqlite, restore_by_{pk}_sync needs derive [UpdateSync] and runs in a SAVEPOINT.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableLogSql,CreateTableSql,HistorySync,InsertSync,UpdateSync};
+/// #[derive(CreateTableSql,CreateTableLogSql,HistorySync,InsertSync,UpdateSync)]
+/// #[sql(log_audit, restore)]
+/// struct Cat {
+///    #[sql(pk)]
+///    id: i64,
+///    name: String,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Cat::CREATE_TABLE_SQL)?;
+///    conn.execute_batch(Cat::CREATE_TABLE_LOG_SQL)?;
+///
+///    let mut c = Cat { id: 0, name: "Tom".to_owned() };
+///    c.id = c.insert_sync(&conn)?;
+///    let at: f64 = conn.query_row("SELECT unixepoch('subsec')", [], |row| row.get(0))?;
+///    std::thread::sleep(std::time::Duration::from_millis(5));
+///    c.name = "Garfield".to_owned();
+///    c.update_sync(&conn)?;
+///
+///    assert_eq!(Cat::history_by_id_sync(c.id, &conn)?[0].name, "Tom");
+///    assert!(Cat::restore_by_id_sync(c.id, at, &conn)?);
+///    assert_eq!(Cat::as_of_by_id_sync(c.id, f64::MAX, &conn)?.unwrap().name, "Tom");
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(HistorySync, attributes(sql))]
+pub fn history_sync(input: TokenStream) -> TokenStream {
+	history_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// fn insert(&self, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<i64, sqlx::Error>
+///
+/// fn upsert(&self, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<i64, sqlx::Error>
+///
+/// `fn insert_many(items: &[Self], conn: impl sqlx::Acquire<'_, Database = sqlx::Sqlite>) -> Result<Vec<i64>, sqlx::Error>`
+///
+/// Generate fn for INSERT with sqlx.
+///
+/// If there is a PRIMARY KEY and the rust-type is i64 then the insert depends on the value of the pk.\
+/// If pk > 0 then do a full insert including the pk, else do a insert without the pk so sqlite assigns a new one,
+/// which is returned as result.
 ///
 /// Without PRIMARY KEY or rust-type != i64 do a full insert and return the last_insert_rowid.
 ///
@@ -360,6 +1127,110 @@ pub fn get(input: TokenStream) -> TokenStream {
 ///    Ok(())
 /// }
 /// ```
+///
+/// ## Insert many
+///
+/// Insert all items in one transaction (a savepoint if `conn` is a transaction) and return the rowids.\
+/// Every item is inserted like `insert`, sqlx reuses the cached prepared statement.
+/// On error the transaction is rolled back.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,Insert};
+/// #[derive(CreateTableSql,Insert)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    name: String,
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(Cat::CREATE_TABLE_SQL).await?;
+///
+///    let cats = [
+///       Cat { id: 0, name: "miau".to_owned() },
+///       Cat { id: 5, name: "meouw".to_owned() },
+///       Cat { id: 0, name: "mew".to_owned() },
+///    ];
+///    let ids = Cat::insert_many(&cats, &mut conn).await?;
+///    assert_eq!(ids, [1, 5, 6]);
+///
+///    Ok(())
+/// }
+/// ```
+///
+/// ## Upsert
+///
+/// `INSERT ... ON CONFLICT({target}) DO UPDATE SET {col}=excluded.{col},... RETURNING rowid`
+///
+/// The conflict target is the PRIMARY KEY or the column(s) of the struct attribute
+/// `#[sql(upsert = "col1,col2")]`, which need a UNIQUE constraint.\
+/// All columns except the PRIMARY KEY and the conflict target are updated.
+/// Not generated if there is no conflict target, no column to update or no rowid
+/// (table option `WITHOUT ROWID`), with the struct attribute `upsert` these are a compile error.\
+/// Returns the rowid of the inserted or updated row.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,Insert};
+/// #[derive(CreateTableSql,Insert)]
+/// #[sql(upsert = "name")]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    #[sql(constraint = "UNIQUE")]
+///    name: String,
+///    lives: u8,
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(Cat::CREATE_TABLE_SQL).await?;
+///
+///    let c = Cat {
+///       id: 0,
+///       name: "miau".to_owned(),
+///       lives: 9,
+///    };
+///    let id = c.upsert(&mut conn).await?;
+///    assert!(id == 1);
+///
+///    let c = Cat {
+///       id: 0,
+///       name: "miau".to_owned(),
+///       lives: 8,
+///    };
+///    let id = c.upsert(&mut conn).await?;
+///    assert!(id == 1);
+///
+///    Ok(())
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use wb_sqlite::Insert;
+/// #[derive(Insert)]
+/// #[sql(upsert = "name", option = "WITHOUT ROWID")]
+/// struct Tag {
+///    #[sql(pk)]
+///    name: String,
+///    color: String,
+/// }
+/// ```
+///
+/// ## Enum
+///
+/// An enum is a compile error, a fieldless enum column needs derive [TextEnum].
+///
+/// ```rust,compile_fail
+/// # use wb_sqlite::Insert;
+/// #[derive(Insert)]
+/// enum Mood {
+///    Happy,
+///    Grumpy,
+/// }
+/// ```
 #[proc_macro_derive(Insert, attributes(sql))]
 pub fn insert(input: TokenStream) -> TokenStream {
 	insert::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
@@ -367,6 +1238,10 @@ pub fn insert(input: TokenStream) -> TokenStream {
 
 /// fn insert_sync(&self, conn: &rusqlite::Connection) -> Result<i64, rusqlite::Error>
 ///
+/// fn upsert_sync(&self, conn: &rusqlite::Connection) -> Result<i64, rusqlite::Error>
+///
+/// `fn insert_many_sync(items: &[Self], conn: &rusqlite::Connection) -> Result<Vec<i64>, rusqlite::Error>`
+///
 /// Generate fn for INSERT with rusqlite.
 ///
 /// If there is a PRIMARY KEY and the rust-type is i64 then the insert depends on the value of the pk.\
@@ -399,18 +1274,188 @@ pub fn insert(input: TokenStream) -> TokenStream {
 ///    Ok(())
 /// }
 /// ```
+///
+/// ## Insert many
+///
+/// Insert all items within a savepoint and return the rowids.\
+/// Every item is inserted like `insert_sync`, which reuses the cached prepared statement.
+/// On error the savepoint is rolled back.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,InsertSync};
+/// #[derive(CreateTableSql,InsertSync)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    name: String,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Cat::CREATE_TABLE_SQL)?;
+///
+///    let cats = [
+///       Cat { id: 0, name: "miau".to_owned() },
+///       Cat { id: 5, name: "meouw".to_owned() },
+///       Cat { id: 0, name: "mew".to_owned() },
+///    ];
+///    let ids = Cat::insert_many_sync(&cats, &conn)?;
+///    assert_eq!(ids, [1, 5, 6]);
+///
+///    Ok(())
+/// }
+/// ```
+///
+/// ## Upsert
+///
+/// `INSERT ... ON CONFLICT({target}) DO UPDATE SET {col}=excluded.{col},... RETURNING rowid`
+///
+/// The conflict target is the PRIMARY KEY or the column(s) of the struct attribute
+/// `#[sql(upsert = "col1,col2")]`, see [Insert], also for the cases without upsert_sync.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,InsertSync};
+/// #[derive(CreateTableSql,InsertSync)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    name: String,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Cat::CREATE_TABLE_SQL)?;
+///
+///    let c = Cat {
+///       id: 7,
+///       name: "miau".to_owned(),
+///    };
+///    let id = c.upsert_sync(&conn)?;
+///    assert!(id == 7);
+///
+///    let c = Cat {
+///       id: 7,
+///       name: "meouw".to_owned(),
+///    };
+///    let id = c.upsert_sync(&conn)?;
+///    assert!(id == 7);
+///
+///    Ok(())
+/// }
+/// ```
+///
+/// ## Enum
+///
+/// An enum is a compile error, a fieldless enum column needs derive [TextEnumSync].
 #[proc_macro_derive(InsertSync, attributes(sql))]
 pub fn insert_sync(input: TokenStream) -> TokenStream {
 	insert_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
 }
 
+/// enum {TypeName}Column { FieldName, ... }
+///
+/// Generate a column identifier enum with a variant for every column.\
+/// `fn name(self) -> &'static str` returns the column name.
+///
+/// Used by `update_columns` of [Update] and `update_columns_sync` of [UpdateSync].
+///
+/// ```rust
+/// # use wb_sqlite::Columns;
+/// #[derive(Columns)]
+/// struct Cat {
+///    id: i64,
+///    sci_name: String,
+/// }
+/// assert_eq!(CatColumn::Id.name(), "id");
+/// assert_eq!(CatColumn::SciName.name(), "sci_name");
+/// ```
+#[proc_macro_derive(Columns, attributes(sql))]
+pub fn columns(input: TokenStream) -> TokenStream {
+	columns::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
 /// fn update(&self, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<bool, sqlx::Error>
 ///
+/// fn update_{field-name}(&self, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<bool, sqlx::Error>
+///
+/// fn update_columns(&self, cols: &[{TypeName}Column], exec: impl sqlx::SqliteExecutor<'_ >) -> Result<bool, sqlx::Error>
+///
 /// Generate fn for UPDATE with sqlx, if there is a PRIMARY KEY.\
 /// PRIMARY KEY detection works only if constraint is in all caps, lowercase serves as escape hatch.
 ///
 /// `UPDATE {tab_name} SET {cols} WHERE {pk}=`
 ///
+/// With the table constraint PRIMARY KEY(a,b) all key columns are in the WHERE clause:
+/// `WHERE a=? AND b=?`
+///
+/// Returns true if the row was updated, false if there is no row with this pk.
+///
+/// `update_{field-name}` updates a single column, for every column except the PRIMARY KEY.\
+/// `update_columns` updates the chosen columns, it is generated with the struct attribute
+/// `#[sql(update_columns)]` and needs derive [Columns].
+/// An empty `cols` updates nothing and returns false.
+///
+/// A field named `sync`, or `columns` with `update_columns`, is a compile error,
+/// its update fn would collide with `update_sync` of [UpdateSync] or with `update_columns`.
+///
+/// ```compile_fail
+/// # use wb_sqlite::Update;
+/// #[derive(Update)]
+/// struct Peer {
+///    #[sql(pk)]
+///    id: i64,
+///    sync: bool,
+/// }
+/// ```
+///
+/// ## Errors
+///
+/// `sqlx::Error::InvalidArgument` if the rust-type of the pk is i64 and pk < 1 (`{pk} 0 < 1`),
+/// or if more than one row was updated because the key is not unique in the table.\
+/// `update_columns` checks `cols` before the update, a PRIMARY KEY, version or readonly column
+/// is `sqlx::Error::InvalidArgument` with the message `{col} is not updatable`.
+///
+/// ## Version
+///
+/// A field with `#[sql(version)]` is used for optimistic locking.\
+/// `UPDATE {tab_name} SET {cols},{version}={version}+1 WHERE {pk}=? AND {version}=?`
+///
+/// The row is stale if it was changed since it was read,
+/// then nothing is updated and `sqlx::Error::RowNotFound` is returned,
+/// update returns this error only for a stale row.
+/// A deleted row is not stale, update returns false as without a version.
+/// After a successful update the version in the database is one higher than in self,
+/// read the row again before the next update.\
+/// Insert starts a new row with version 0, upsert increments the version.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,Get,Insert,Update};
+/// #[derive(CreateTableSql,Get,Insert,Update,sqlx::FromRow)]
+/// struct Account {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    balance: i64,
+///    #[sql(version)]
+///    version: i64,
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(Account::CREATE_TABLE_SQL).await?;
+///
+///    let a = Account { id: 0, balance: 10, version: 0 };
+///    let id = a.insert(&mut conn).await?;
+///
+///    let mut a1 = Account::get_by_id(id, &mut conn).await?;
+///    let mut a2 = Account::get_by_id(id, &mut conn).await?;
+///    a1.balance = 20;
+///    assert!(a1.update(&mut conn).await?);
+///    a2.balance = 30;
+///    assert!(matches!(a2.update(&mut conn).await, Err(sqlx::Error::RowNotFound)));
+///
+///    Ok(())
+/// }
+/// ```
+///
 /// ```rust
 /// # use wb_sqlite::{CreateTableSql,Insert,Update};
 /// #[derive(CreateTableSql,Insert,Update)]
@@ -449,18 +1494,51 @@ pub fn update(input: TokenStream) -> TokenStream {
 
 /// fn update_sync(&self, conn: &rusqlite::Connection) -> Result<bool, rusqlite::Error>
 ///
+/// fn update_{field-name}_sync(&self, conn: &rusqlite::Connection) -> Result<bool, rusqlite::Error>
+///
+/// fn update_columns_sync(&self, cols: &[{TypeName}Column], conn: &rusqlite::Connection) -> Result<bool, rusqlite::Error>
+///
 /// Generate fn for UPDATE with rusqlite, if there is a PRIMARY KEY.\
 /// PRIMARY KEY detection works only if constraint is in all caps, lowercase serves as escape hatch.
 ///
 /// `UPDATE {tab_name} SET {cols} WHERE {pk}=`
 ///
+/// With the table constraint PRIMARY KEY(a,b) all key columns are in the WHERE clause:
+/// `WHERE a=? AND b=?`
+///
+/// Returns true if the row was updated, false if there is no row with this pk.
+///
+/// `update_{field-name}_sync` updates a single column, for every column except the PRIMARY KEY.\
+/// `update_columns_sync` updates the chosen columns, it is generated with the struct attribute
+/// `#[sql(update_columns)]` and needs derive [Columns].
+/// An empty `cols` updates nothing and returns false.
+///
+/// A field named `columns` with `update_columns` is a compile error,
+/// its `update_columns_sync` would collide with the one for the chosen columns.
+///
+/// ## Errors
+///
+/// `rusqlite::Error::ToSqlConversionFailure` if the rust-type of the pk is i64 and pk < 1,
+/// with the message of the sqlx InvalidArgument of [Update]: `{pk} 0 < 1`.\
+/// `rusqlite::Error::StatementChangedRows` if more than one row was updated.\
+/// `rusqlite::Error::InvalidColumnName("{col} is not updatable")` if `update_columns_sync` gets
+/// a PRIMARY KEY, version or readonly column.
+///
+/// ## Version
+///
+/// A field with `#[sql(version)]` is used for optimistic locking, see [Update].\
+/// A stale row is not updated and `rusqlite::Error::QueryReturnedNoRows` is returned,
+/// a deleted row returns false.
+///
 /// ```rust
-/// # use wb_sqlite::{CreateTableSql,InsertSync,UpdateSync};
-/// #[derive(CreateTableSql,InsertSync,UpdateSync)]
+/// # use wb_sqlite::{Columns,CreateTableSql,InsertSync,UpdateSync};
+/// #[derive(Columns,CreateTableSql,InsertSync,UpdateSync)]
+/// #[sql(update_columns)]
 /// struct Cat {
 ///    #[sql(constraint = "PRIMARY KEY")]
 ///    id: i64,
 ///    name: String,
+///    lives: u8,
 /// }
 /// fn main() -> Result<(), rusqlite::Error> {
 ///    let conn = rusqlite::Connection::open_in_memory()?;
@@ -469,6 +1547,7 @@ pub fn update(input: TokenStream) -> TokenStream {
 ///    let c = Cat {
 ///       id: 0,
 ///       name: "miau".to_owned(),
+///       lives: 9,
 ///    };
 ///    let id = c.insert_sync(&conn)?;
 ///    assert!(id == 1);
@@ -476,10 +1555,21 @@ pub fn update(input: TokenStream) -> TokenStream {
 ///    let c2 = Cat {
 ///       id: 1,
 ///       name: "meouw".to_owned(),
+///       lives: 9,
 ///    };
 ///    let ok = c2.update_sync(&conn)?;
 ///    assert!(ok);
 ///
+///    let c3 = Cat {
+///       id: 1,
+///       name: "mew".to_owned(),
+///       lives: 7,
+///    };
+///    let ok = c3.update_lives_sync(&conn)?;
+///    assert!(ok);
+///    let ok = c3.update_columns_sync(&[CatColumn::Name], &conn)?;
+///    assert!(ok);
+///
 ///    Ok(())
 /// }
 /// ```
@@ -487,3 +1577,227 @@ pub fn update(input: TokenStream) -> TokenStream {
 pub fn update_sync(input: TokenStream) -> TokenStream {
 	update_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
 }
+
+/// fn delete(&self, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<bool, sqlx::Error>
+///
+/// fn delete_by_{field-name}({field-name}: {field-type}, exec: impl sqlx::SqliteExecutor<'_ >) -> Result<bool, sqlx::Error>
+///
+/// Generate fn for DELETE with sqlx.\
+/// `delete` if there is a PRIMARY KEY, `delete_by_{field-name}` for PRIMARY KEY and every UNIQUE constraint.\
+/// Works only if constraint is in all caps, lowercase serves as escape hatch.
+///
+/// `DELETE FROM {tab_name} WHERE {field-name}=`
+///
+/// The table constraints PRIMARY KEY(a,b) and UNIQUE(a,b) generate `delete_by_a_and_b`
+/// and `delete` uses all key columns.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,Delete,Insert};
+/// #[derive(CreateTableSql,Delete,Insert)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    #[sql(constraint = "UNIQUE")]
+///    name: String,
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(Cat::CREATE_TABLE_SQL).await?;
+///
+///    let c = Cat {
+///       id: 0,
+///       name: "miau".to_owned(),
+///    };
+///    let id = c.insert(&mut conn).await?;
+///    assert!(id == 1);
+///
+///    let c = Cat {
+///       id: 1,
+///       name: "miau".to_owned(),
+///    };
+///    let ok = c.delete(&mut conn).await?;
+///    assert!(ok);
+///
+///    let ok = Cat::delete_by_name("miau", &mut conn).await?;
+///    assert!(!ok);
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(Delete, attributes(sql))]
+pub fn delete(input: TokenStream) -> TokenStream {
+	delete::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// fn delete_sync(&self, conn: &rusqlite::Connection) -> Result<bool, rusqlite::Error>
+///
+/// fn delete_by_{field-name}_sync({field-name}: {field-type}, conn: &rusqlite::Connection) -> Result<bool, rusqlite::Error>
+///
+/// Generate fn for DELETE with rusqlite.\
+/// `delete_sync` if there is a PRIMARY KEY, `delete_by_{field-name}_sync` for PRIMARY KEY and every UNIQUE constraint.\
+/// Works only if constraint is in all caps, lowercase serves as escape hatch.
+///
+/// `DELETE FROM {tab_name} WHERE {field-name}=`
+///
+/// The table constraints PRIMARY KEY(a,b) and UNIQUE(a,b) generate `delete_by_a_and_b`
+/// and `delete` uses all key columns.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,DeleteSync,InsertSync};
+/// #[derive(CreateTableSql,DeleteSync,InsertSync)]
+/// struct Cat {
+///    #[sql(constraint = "PRIMARY KEY")]
+///    id: i64,
+///    #[sql(constraint = "UNIQUE")]
+///    name: String,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Cat::CREATE_TABLE_SQL)?;
+///
+///    let c = Cat {
+///       id: 0,
+///       name: "miau".to_owned(),
+///    };
+///    let id = c.insert_sync(&conn)?;
+///    assert!(id == 1);
+///
+///    let ok = Cat::delete_by_name_sync("miau", &conn)?;
+///    assert!(ok);
+///
+///    let ok = Cat::delete_by_id_sync(1, &conn)?;
+///    assert!(!ok);
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(DeleteSync, attributes(sql))]
+pub fn delete_sync(input: TokenStream) -> TokenStream {
+	delete_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// `const FLATTEN_COLUMNS / FLATTEN_COL_TYPES / FLATTEN_COL_DEFS / FLATTEN_COL_CHECKS: &'static [&'static str]`
+///
+/// The column names, datatypes, column definitions and type checks of a struct,
+/// which is a `#[sql(flatten)]` field of another struct.
+/// The derives of the outer struct prefix the column names, see [CreateTableSql].\
+/// The nested struct can not have version, readonly, generated, pk or flatten fields.\
+/// The range and json_valid checks of [CreateTableSql] name the column as `?`,
+/// CREATE_TABLE_SQL of the outer struct puts in the prefixed column name.\
+/// Constraint expressions are not prefixed, a check must not name a column of the nested struct.
+///
+/// ```rust
+/// # use wb_sqlite::FlattenSql;
+/// #[derive(FlattenSql)]
+/// struct Address {
+///    street: String,
+///    #[sql(default = "0")]
+///    zip: u16,
+/// }
+/// assert_eq!(Address::FLATTEN_COLUMNS, ["street", "zip"]);
+/// assert_eq!(Address::FLATTEN_COL_TYPES, ["TEXT NOT NULL", "INTEGER NOT NULL"]);
+/// assert_eq!(
+///    Address::FLATTEN_COL_DEFS,
+///    ["TEXT NOT NULL", "INTEGER NOT NULL DEFAULT 0"]
+/// );
+/// assert_eq!(Address::FLATTEN_COL_CHECKS, ["", "CHECK (? BETWEEN 0 AND 65535)"]);
+/// ```
+#[proc_macro_derive(FlattenSql, attributes(sql))]
+pub fn flatten_sql(input: TokenStream) -> TokenStream {
+	flatten_sql::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// `fn flatten_bind(&self, query) -> query`\
+/// `fn flatten_from_row(row: &sqlx::sqlite::SqliteRow, prefix: &str) -> Result<Self, sqlx::Error>`
+///
+/// sqlx support of a `#[sql(flatten)]` field, used by [Insert] and [Update] of the outer struct.\
+/// sqlx::FromRow can not flatten with a prefix,
+/// implement it for the outer struct with flatten_from_row.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,Flatten,FlattenSql,Insert,SelectSql};
+/// #[derive(Flatten,FlattenSql)]
+/// struct Address {
+///    street: String,
+///    city: String,
+/// }
+/// #[derive(CreateTableSql,Insert,SelectSql)]
+/// struct Shop {
+///    #[sql(pk)]
+///    id: i64,
+///    #[sql(flatten)]
+///    address: Address,
+/// }
+/// impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Shop {
+///    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+///       use sqlx::Row;
+///       Ok(Self {
+///          id: row.try_get("id")?,
+///          address: Address::flatten_from_row(row, "address_")?,
+///       })
+///    }
+/// }
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), sqlx::Error> {
+///    use sqlx::{Connection, Executor};
+///    let mut conn = sqlx::SqliteConnection::connect(":memory:").await?;
+///    conn.execute(Shop::CREATE_TABLE_SQL).await?;
+///
+///    let s = Shop {
+///       id: 0,
+///       address: Address { street: "Main St".to_owned(), city: "Vienna".to_owned() },
+///    };
+///    s.insert(&mut conn).await?;
+///    let s2: Shop = sqlx::query_as(Shop::SELECT_SQL).fetch_one(&mut conn).await?;
+///    assert_eq!(s2.address.city, "Vienna");
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(Flatten, attributes(sql))]
+pub fn flatten(input: TokenStream) -> TokenStream {
+	flatten::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
+
+/// `fn flatten_params(&self, params: &mut Vec<&dyn rusqlite::ToSql>)`\
+/// `fn flatten_from_row_sync(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<Self>`\
+/// `fn flatten_from_row_by_name_sync(row: &rusqlite::Row<'_>, prefix: &str) -> rusqlite::Result<Self>`
+///
+/// rusqlite support of a `#[sql(flatten)]` field,
+/// used by [InsertSync], [UpdateSync], [GetSync] and [FromRowSync] of the outer struct.
+///
+/// ```rust
+/// # use wb_sqlite::{CreateTableSql,FlattenSql,FlattenSync,GetSync,InsertSync};
+/// #[derive(Debug,PartialEq,FlattenSql,FlattenSync)]
+/// struct Address {
+///    street: String,
+///    city: String,
+/// }
+/// #[derive(CreateTableSql,GetSync,InsertSync)]
+/// struct Shop {
+///    #[sql(pk)]
+///    id: i64,
+///    #[sql(flatten, prefix = "")]
+///    address: Address,
+/// }
+/// fn main() -> Result<(), rusqlite::Error> {
+///    let conn = rusqlite::Connection::open_in_memory()?;
+///    conn.execute_batch(Shop::CREATE_TABLE_SQL)?;
+///
+///    let s = Shop {
+///       id: 0,
+///       address: Address { street: "Main St".to_owned(), city: "Vienna".to_owned() },
+///    };
+///    let id = s.insert_sync(&conn)?;
+///    let s2 = Shop::get_by_id_sync(id, &conn)?;
+///    assert_eq!(s2.address, s.address);
+///
+///    Ok(())
+/// }
+/// ```
+#[proc_macro_derive(FlattenSync, attributes(sql))]
+pub fn flatten_sync(input: TokenStream) -> TokenStream {
+	flatten_sync::inner(input).unwrap_or_else(virtue::Error::into_token_stream)
+}
@@ -8,7 +8,12 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
 		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"SelectSql",
+			));
+		}
 	};
 	generator.export_to_file("wb_sqlite", "SelectSql");
 	generator.finish()
@@ -16,28 +21,28 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 
 fn gen_struct(
 	generator: &mut Generator,
-	_attributes: Vec<Attribute>,
+	attributes: Vec<Attribute>,
 	fields: Option<Fields>,
 ) -> Result {
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let tab_name = crate::util::table_ident(&generator.target_name().to_string(), &tab_attr);
 
-	let mut columns = String::new();
-	for (ident, _uf) in struct_fields {
-		columns.push_str(&ident.to_string());
-		columns.push(',');
-	}
-	columns.pop(); // get rid of the last ','
+	let columns = crate::util::select_list(&crate::util::cols(struct_fields, &tab_attr)?);
 
 	if !columns.is_empty() {
+		let mut sql = crate::util::Sql::default();
+		sql.push_str("SELECT ");
+		sql.push_sql(columns);
+		sql.push_str(&format!(" FROM {tab_name}"));
 		generator
 			.generate_impl()
 			.generate_const("SELECT_SQL", "&'static str")
 			.make_pub()
 			.with_value(|b| {
-				b.push_parsed(format!("\"SELECT {columns} FROM {tab_name}\""))?;
+				b.push_parsed(sql.expr())?;
 				Ok(())
 			})?;
 	}
@@ -8,7 +8,12 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
 		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"SelectAsSql",
+			));
+		}
 	};
 	generator.export_to_file("wb_sqlite", "SelectAsSql");
 	generator.finish()
@@ -22,26 +27,44 @@ fn gen_struct(
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
+	let sql_tab_attr = crate::util::tab_attr(&attributes)?;
 	let tab_attr = attributes
 		.get_attribute::<crate::util::AsTabAttr>()?
 		.unwrap_or_default();
 	let from = if tab_attr.from.is_empty() {
-		tab_name
+		crate::util::table_ident(&generator.target_name().to_string(), &sql_tab_attr)
 	} else {
 		tab_attr.from
 	};
 
-	let mut columns = String::new();
-	for (ident, uf) in struct_fields {
+	// field-name -> sqlas col
+	let mut as_cols = Vec::with_capacity(struct_fields.len());
+	for (ident, uf) in &struct_fields {
 		let col_attr = uf
 			.attributes
 			.get_attribute::<crate::util::AsColAttr>()?
 			.unwrap_or_default();
-		if col_attr.col.is_empty() {
-			columns.push_str(&ident.to_string());
+		as_cols.push((ident.to_string(), col_attr.col));
+	}
+	let mut columns = String::new();
+	for col in crate::util::cols(struct_fields, &sql_tab_attr)? {
+		if col.is_flatten() {
+			return Err(virtue::Error::custom(format!(
+				"flatten is not supported by SelectAsSql, field {}",
+				col.field
+			)));
+		}
+		let as_col = as_cols
+			.iter()
+			.find(|(field, _)| *field == col.field)
+			.map_or("", |(_, as_col)| as_col.as_str());
+		if as_col.is_empty() {
+			columns.push_str(&col.select_expr());
 		} else {
-			columns.push_str(&format!("{} AS {}", col_attr.col, ident));
+			columns.push_str(&format!(
+				"{as_col} AS {}",
+				crate::util::quote(col.field_name(), sql_tab_attr.quote_all)
+			));
 		};
 		columns.push(',');
 	}
@@ -0,0 +1,83 @@
+use virtue::{
+	parse::EnumBody,
+	prelude::{AttributeAccess, Body, Error, Fields, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, _attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(struct_body) => gen_struct(&mut generator, struct_body.fields)?,
+		Body::Enum(enum_body) => gen_enum(&mut generator, enum_body)?,
+	};
+	generator.export_to_file("wb_sqlite", "SqlType");
+	generator.finish()
+}
+
+/// The datatype of the one field of a newtype.
+fn gen_struct(generator: &mut Generator, fields: Option<Fields>) -> Result {
+	let uf = match fields {
+		Some(Fields::Tuple(mut fields)) if fields.len() == 1 => fields.remove(0),
+		Some(Fields::Struct(mut fields)) if fields.len() == 1 => fields.remove(0).1,
+		_ => {
+			return Err(Error::custom_at(
+				"SqlType needs a newtype, a struct with one field",
+				generator.target_name().span(),
+			));
+		}
+	};
+	let attr = uf
+		.attributes
+		.get_attribute::<crate::util::ColAttr>()?
+		.unwrap_or_default();
+	let typ = crate::util::type_string(&uf);
+	let (inner, nullable) = crate::util::sql_type_path(&typ);
+	let (sql_type, nullable) = if !attr.typ.is_empty() {
+		(
+			format!("\"{}\"", attr.typ.trim_end_matches(" NOT NULL")),
+			nullable.to_string(),
+		)
+	} else {
+		crate::util::integer_range(&typ, &uf)?;
+		match crate::util::col_typ(&typ) {
+			// a newtype of a newtype
+			"ANY" => {
+				let any = crate::util::SQL_TYPE_ANY;
+				(
+					format!("{{ {any} <{inner}>::SQL_TYPE }}"),
+					format!("{{ {any} {nullable} || <{inner}>::NULLABLE }}"),
+				)
+			}
+			datatype => (
+				format!("\"{}\"", datatype.trim_end_matches(" NOT NULL")),
+				nullable.to_string(),
+			),
+		}
+	};
+	gen_consts(generator, &sql_type, &nullable)
+}
+
+/// A fieldless enum is stored as TEXT, the variant name.
+fn gen_enum(generator: &mut Generator, enum_body: EnumBody) -> Result {
+	crate::util::enum_variants(&generator.target_name(), enum_body)?;
+	gen_consts(generator, "\"TEXT\"", "false")
+}
+
+fn gen_consts(generator: &mut Generator, sql_type: &str, nullable: &str) -> Result {
+	let mut gen_impl = generator.generate_impl();
+	gen_impl
+		.generate_const("SQL_TYPE", "&'static str")
+		.make_pub()
+		.with_value(|b| {
+			b.push_parsed(sql_type)?;
+			Ok(())
+		})?;
+	gen_impl
+		.generate_const("NULLABLE", "bool")
+		.make_pub()
+		.with_value(|b| {
+			b.push_parsed(nullable)?;
+			Ok(())
+		})?;
+	Ok(())
+}
@@ -0,0 +1,82 @@
+use virtue::{
+	parse::EnumBody,
+	prelude::{Body, Error, FnSelfArg, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, _attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(_struct_body) => {
+			return Err(Error::custom_at(
+				"TextEnum needs a fieldless enum",
+				generator.target_name().span(),
+			));
+		}
+		Body::Enum(enum_body) => gen_enum(&mut generator, enum_body)?,
+	};
+	generator.export_to_file("wb_sqlite", "TextEnum");
+	generator.finish()
+}
+
+/// sqlx Type, Encode and Decode of the variant name as TEXT.
+fn gen_enum(generator: &mut Generator, enum_body: EnumBody) -> Result {
+	let variants = crate::util::enum_variants(&generator.target_name(), enum_body)?;
+	let name = generator.target_name().to_string();
+
+	let mut impl_type = generator.impl_for("::sqlx::Type<::sqlx::Sqlite>");
+	impl_type
+		.generate_fn("type_info")
+		.with_return_type("::sqlx::sqlite::SqliteTypeInfo")
+		.body(|fn_body| {
+			fn_body.push_parsed("<str as ::sqlx::Type<::sqlx::Sqlite>>::type_info()")?;
+			Ok(())
+		})?;
+	impl_type
+		.generate_fn("compatible")
+		.with_arg("ty", "&::sqlx::sqlite::SqliteTypeInfo")
+		.with_return_type("bool")
+		.body(|fn_body| {
+			fn_body.push_parsed("<str as ::sqlx::Type<::sqlx::Sqlite>>::compatible(ty)")?;
+			Ok(())
+		})?;
+	drop(impl_type);
+
+	generator
+		.impl_for_with_lifetimes("::sqlx::Encode", ["q"])
+		.with_trait_generics(["::sqlx::Sqlite"])
+		.generate_fn("encode_by_ref")
+		.with_self_arg(FnSelfArg::RefSelf)
+		.with_arg(
+			"buf",
+			"&mut <::sqlx::Sqlite as ::sqlx::Database>::ArgumentBuffer<'q>",
+		)
+		.with_return_type("Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError>")
+		.body(|fn_body| {
+			fn_body.push_parsed(format!(
+				"let s: &'static str = {}; <&str as ::sqlx::Encode<'q, ::sqlx::Sqlite>>::encode(s, buf)",
+				crate::util::enum_to_str(&variants)
+			))?;
+			Ok(())
+		})?;
+
+	generator
+		.impl_for_with_lifetimes("::sqlx::Decode", ["r"])
+		.with_trait_generics(["::sqlx::Sqlite"])
+		.generate_fn("decode")
+		.with_arg(
+			"value",
+			"<::sqlx::Sqlite as ::sqlx::Database>::ValueRef<'r>",
+		)
+		.with_return_type("Result<Self, ::sqlx::error::BoxDynError>")
+		.body(|fn_body| {
+			fn_body.push_parsed(crate::util::enum_from_str(
+				&variants,
+				"<&str as ::sqlx::Decode<'r, ::sqlx::Sqlite>>::decode(value)?",
+				&format!("Err(\"invalid {name} variant\".into())"),
+			))?;
+			Ok(())
+		})?;
+
+	Ok(())
+}
@@ -0,0 +1,57 @@
+use virtue::{
+	parse::EnumBody,
+	prelude::{Body, Error, FnSelfArg, Generator, Parse, Result, TokenStream},
+};
+
+pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
+	let parse = Parse::new(input)?;
+	let (mut generator, _attributes, body) = parse.into_generator();
+	match body {
+		Body::Struct(_struct_body) => {
+			return Err(Error::custom_at(
+				"TextEnumSync needs a fieldless enum",
+				generator.target_name().span(),
+			));
+		}
+		Body::Enum(enum_body) => gen_enum(&mut generator, enum_body)?,
+	};
+	generator.export_to_file("wb_sqlite", "TextEnumSync");
+	generator.finish()
+}
+
+/// rusqlite ToSql and FromSql of the variant name as TEXT.
+fn gen_enum(generator: &mut Generator, enum_body: EnumBody) -> Result {
+	let variants = crate::util::enum_variants(&generator.target_name(), enum_body)?;
+	let name = generator.target_name().to_string();
+
+	generator
+		.impl_for("::rusqlite::ToSql")
+		.generate_fn("to_sql")
+		.with_self_arg(FnSelfArg::RefSelf)
+		.with_return_type("::rusqlite::Result<::rusqlite::types::ToSqlOutput<'_>>")
+		.body(|fn_body| {
+			fn_body.push_parsed(format!(
+				"let s: &'static str = {}; Ok(::rusqlite::types::ToSqlOutput::from(s))",
+				crate::util::enum_to_str(&variants)
+			))?;
+			Ok(())
+		})?;
+
+	generator
+		.impl_for("::rusqlite::types::FromSql")
+		.generate_fn("column_result")
+		.with_arg("value", "::rusqlite::types::ValueRef<'_>")
+		.with_return_type("::rusqlite::types::FromSqlResult<Self>")
+		.body(|fn_body| {
+			fn_body.push_parsed(crate::util::enum_from_str(
+				&variants,
+				"value.as_str()?",
+				&format!(
+					"Err(::rusqlite::types::FromSqlError::Other(\"invalid {name} variant\".into()))"
+				),
+			))?;
+			Ok(())
+		})?;
+
+	Ok(())
+}
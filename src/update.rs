@@ -1,6 +1,6 @@
 use virtue::{
 	parse::Attribute,
-	prelude::{AttributeAccess, Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
+	prelude::{Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
 };
 
 pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
@@ -8,7 +8,12 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
 		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"Update",
+			));
+		}
 	};
 	generator.export_to_file("wb_sqlite", "Update");
 	generator.finish()
@@ -16,49 +21,82 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 
 fn gen_struct(
 	generator: &mut Generator,
-	_attributes: Vec<Attribute>,
+	attributes: Vec<Attribute>,
 	fields: Option<Fields>,
 ) -> Result {
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
+	let target_name = generator.target_name().to_string();
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let tab_name = crate::util::table_ident(&target_name, &tab_attr);
+	crate::util::integer_ranges(&struct_fields)?;
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	let ver = crate::util::version_col(&cols);
 
-	let mut pk = String::new();
-	let mut columns = String::new();
-	for (ident, uf) in struct_fields {
-		let col_attr = uf
-			.attributes
-			.get_attribute::<crate::util::ColAttr>()?
-			.unwrap_or_default();
-		if col_attr.constraint.starts_with("PRIMARY KEY") {
-			pk = ident.to_string();
-		} else {
-			columns.push_str(&ident.to_string());
-			columns.push(',');
-		}
-	}
-	// get rid of the last ','
-	columns.pop();
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+	let pk = crate::util::key_where(&keys.pk);
+	let pk_bind: String = keys
+		.pk
+		.iter()
+		.map(|c| format!(".bind(&self.{})", c.field))
+		.collect();
+
+	let columns: Vec<&crate::util::Col> = cols
+		.iter()
+		.filter(|c| !(keys.is_pk(c) || c.attr.version || c.attr.is_readonly()))
+		.collect();
+
+	// a versioned row which was not updated is stale if it still exists, the SELECT of the
+	// same query tells it apart from a deleted row, exec can run only one query
+	let stale_select = format!("; SELECT changes(),EXISTS(SELECT 1 FROM {tab_name} WHERE {pk})");
+	let stale_check = "let row = query.fetch_one(exec).await?; let rows: i64 = ::sqlx::Row::try_get(&row, 0)?; if rows == 0 && ::sqlx::Row::try_get(&row, 1)? { return Err(::sqlx::Error::RowNotFound); }";
 
-	fn gen_query(tab_name: &str, pk: &str, columns: &str) -> String {
-		let mut s = format!("::sqlx::query(\"UPDATE {tab_name} SET ");
-		for c in columns.split(',') {
-			s.push_str(&format!("{c}=?,"))
+	fn gen_query(
+		tab_name: &str,
+		pk: &str,
+		pk_bind: &str,
+		columns: &[&crate::util::Col],
+		ver: Option<&crate::util::Col>,
+		(stale_select, stale_check): (&str, &str),
+	) -> String {
+		let mut sql = crate::util::update_sql(tab_name, columns, pk, ver);
+		if ver.is_some() {
+			sql.push_str(stale_select);
 		}
-		s.pop();
-		s.push_str(&format!(" WHERE {pk}=?\")"));
-		for c in columns.split(',') {
-			s.push_str(&format!(".bind(&self.{c})"))
+		let mut s = crate::util::bind(format!("::sqlx::query({})", sql.expr()), columns);
+		s.push_str(pk_bind);
+		match ver {
+			None => format!("let rows = {s}.execute(exec).await?.rows_affected();"),
+			Some(ver) => format!(
+				"let query = {s}.bind(&self.{}){pk_bind}; {stale_check}",
+				ver.field
+			),
 		}
-		s.push_str(&format!(".bind(&self.{pk})"));
-		s.push_str(".execute(exec).await?.rows_affected()");
-		s
 	}
 
+	// RowNotFound is left for a stale row, a bad or not unique key is an InvalidArgument
+	let pk_check = match keys.single_pk() {
+		Some(pk) if pk.is_i64() => format!(
+			"if self.{0} < 1 {{ return Err(::sqlx::Error::InvalidArgument(format!(\"{1} {{}} < 1\", self.{0}))); }}",
+			pk.field,
+			pk.field_name()
+		),
+		_ => String::new(),
+	};
+	let rows_check = format!(
+		"if rows > 1 {{ return Err(::sqlx::Error::InvalidArgument(format!(\"UPDATE {tab_name} affected {{rows}} rows, the key is not unique\"))); }} Ok(rows == 1)"
+	);
+
 	if !(pk.is_empty() || columns.is_empty()) {
-		generator
-			.generate_impl()
+		// update_sync of derive UpdateSync
+		let mut taken = vec!["sync"];
+		if tab_attr.update_columns {
+			taken.push("columns");
+		}
+		crate::util::update_fn_check(&columns, &taken, "")?;
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
 			.generate_fn("update")
 			.as_async()
 			.with_self_arg(FnSelfArg::RefSelf)
@@ -66,15 +104,89 @@ fn gen_struct(
 			.with_return_type("Result<bool, ::sqlx::Error>")
 			.make_pub()
 			.body(|fn_body| {
-				let mut s = String::new();
-				// ToDo Ext: for non i64 pk this has to be modified e.g. assert self.pk.is_not_empty()
-				// ToDo: Document assert / panics
-				s.push_str(&format!("assert!(self.{pk} > 0); let rows = "));
-				s.push_str(&gen_query(&tab_name, &pk, &columns));
-				s.push_str("; assert!(rows < 2); Ok(rows == 1)");
-				fn_body.push_parsed(s)?;
+				fn_body.push_parsed(format!(
+					"{pk_check} {} {rows_check}",
+					gen_query(
+						&tab_name,
+						&pk,
+						&pk_bind,
+						&columns,
+						ver,
+						(&stale_select, stale_check)
+					)
+				))?;
 				Ok(())
 			})?;
+		for col in &columns {
+			gen_impl
+				.generate_fn(format!("update_{}", col.field_name()))
+				.as_async()
+				.with_self_arg(FnSelfArg::RefSelf)
+				.with_arg("exec", "impl ::sqlx::SqliteExecutor<'_>")
+				.with_return_type("Result<bool, ::sqlx::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					fn_body.push_parsed(format!(
+						"{pk_check} {} {rows_check}",
+						gen_query(
+							&tab_name,
+							&pk,
+							&pk_bind,
+							&[col],
+							ver,
+							(&stale_select, stale_check)
+						)
+					))?;
+					Ok(())
+				})?;
+		}
+		if tab_attr.update_columns {
+			let enum_name = crate::util::col_enum_name(&target_name);
+			let col_check = crate::util::not_updatable_check(
+				&cols,
+				&keys,
+				&enum_name,
+				"::sqlx::Error::InvalidArgument(msg)",
+			);
+			let (where_clause, exec_query) = match ver {
+				None => (
+					format!(" WHERE {pk}"),
+					format!("let rows = query{pk_bind}.execute(exec).await?.rows_affected();"),
+				),
+				Some(ver) => (
+					format!(",{0}={0}+1 WHERE {pk} AND {0}=?{stale_select}", ver.ident()),
+					format!(
+						"let query = query{pk_bind}.bind(&self.{}){pk_bind}; {stale_check}",
+						ver.field
+					),
+				),
+			};
+			gen_impl
+				.generate_fn("update_columns")
+				.as_async()
+				.with_self_arg(FnSelfArg::RefSelf)
+				.with_arg("cols", format!("&[{enum_name}]"))
+				.with_arg("exec", "impl ::sqlx::SqliteExecutor<'_>")
+				.with_return_type("Result<bool, ::sqlx::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					let mut s = format!(
+						"{pk_check} {col_check} if cols.is_empty() {{ return Ok(false); }} let mut sql = String::from(\"UPDATE {tab_name} SET \"); for c in cols {{ sql.push('\\\"'); sql.push_str(c.name()); sql.push_str(\"\\\"=?,\"); }} sql.pop(); sql.push_str(\"{where_clause}\"); let mut query = ::sqlx::query(&sql); for c in cols {{ query = match c {{"
+					);
+					for c in cols.iter().filter(|c| !c.is_flatten()) {
+						s.push_str(&format!(
+							"{enum_name}::{} => query.bind(&self.{}),",
+							crate::util::col_variant(c.field_name()),
+							c.field
+						));
+					}
+					s.push_str(&format!(
+						"}}; }} {exec_query} {rows_check}"
+					));
+					fn_body.push_parsed(s)?;
+					Ok(())
+				})?;
+		}
 	}
 
 	Ok(())
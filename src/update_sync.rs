@@ -1,6 +1,6 @@
 use virtue::{
 	parse::Attribute,
-	prelude::{AttributeAccess, Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
+	prelude::{Body, Fields, FnSelfArg, Generator, Parse, Result, TokenStream},
 };
 
 pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
@@ -8,7 +8,12 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 	let (mut generator, attributes, body) = parse.into_generator();
 	match body {
 		Body::Struct(struct_body) => gen_struct(&mut generator, attributes, struct_body.fields)?,
-		Body::Enum(_enum_body) => unimplemented!(),
+		Body::Enum(_enum_body) => {
+			return Err(crate::util::enum_unsupported(
+				&generator.target_name(),
+				"UpdateSync",
+			));
+		}
 	};
 	generator.export_to_file("wb_sqlite", "UpdateSync");
 	generator.finish()
@@ -16,65 +21,151 @@ pub(crate) fn inner(input: TokenStream) -> Result<TokenStream> {
 
 fn gen_struct(
 	generator: &mut Generator,
-	_attributes: Vec<Attribute>,
+	attributes: Vec<Attribute>,
 	fields: Option<Fields>,
 ) -> Result {
 	let Some(Fields::Struct(struct_fields)) = fields else {
 		return Ok(());
 	};
-	let tab_name = crate::util::tab_name(&generator.target_name().to_string());
+	let target_name = generator.target_name().to_string();
+	let tab_attr = crate::util::tab_attr(&attributes)?;
+	let tab_name = crate::util::table_ident(&target_name, &tab_attr);
+	crate::util::integer_ranges(&struct_fields)?;
+	let cols = crate::util::cols(struct_fields, &tab_attr)?;
+	let ver = crate::util::version_col(&cols);
 
-	let mut pk = String::new();
-	let mut columns = String::new();
-	for (ident, uf) in struct_fields {
-		let col_attr = uf
-			.attributes
-			.get_attribute::<crate::util::ColAttr>()?
-			.unwrap_or_default();
-		if col_attr.constraint.starts_with("PRIMARY KEY") {
-			pk = ident.to_string();
-		} else {
-			columns.push_str(&ident.to_string());
-			columns.push(',');
-		}
-	}
-	// get rid of the last ','
-	columns.pop();
+	let keys = crate::util::keys(&cols, &tab_attr.constraint)?;
+	let pk = crate::util::key_where(&keys.pk);
 
-	fn exec(tab_name: &str, pk: &str, columns: &str) -> String {
-		let mut s = format!("let mut stmt = conn.prepare_cached(\"UPDATE {tab_name} SET ");
-		for c in columns.split(',') {
-			s.push_str(&format!("{c}=?,"))
-		}
-		s.pop();
-		s.push_str(&format!(" WHERE {pk}=?\")?;"));
+	let columns: Vec<&crate::util::Col> = cols
+		.iter()
+		.filter(|c| !(keys.is_pk(c) || c.attr.version || c.attr.is_readonly()))
+		.collect();
 
-		s.push_str("let rows = stmt.execute(::rusqlite::params![");
-		for c in columns.split(',') {
-			s.push_str(&format!("self.{c},"))
-		}
-		s.push_str(&format!("self.{pk}"));
-		s.push_str("])?;");
-		s
+	fn exec(
+		tab_name: &str,
+		pk: &str,
+		pk_cols: &[&crate::util::Col],
+		columns: &[&crate::util::Col],
+		ver: Option<&crate::util::Col>,
+	) -> String {
+		let sql = crate::util::update_sql(tab_name, columns, pk, ver);
+		let mut extra = pk_cols.to_vec();
+		extra.extend(ver);
+		format!(
+			"let mut stmt = conn.prepare_cached({})?; let rows = stmt.execute({})?;",
+			sql.expr(),
+			crate::util::params_sync(columns, &extra)
+		)
 	}
 
+	// the same message as the InvalidArgument of derive Update
+	let pk_check = match keys.single_pk() {
+		Some(pk) if pk.is_i64() => format!(
+			"if self.{0} < 1 {{ return Err(::rusqlite::Error::ToSqlConversionFailure(format!(\"{1} {{}} < 1\", self.{0}).into())); }}",
+			pk.field,
+			pk.field_name()
+		),
+		_ => String::new(),
+	};
+	// a versioned row which was not updated is stale if it still exists, else it was deleted
+	let stale_check = if ver.is_none() {
+		String::new()
+	} else {
+		let pk_params = keys
+			.pk
+			.iter()
+			.map(|c| format!("self.{}", c.field))
+			.collect::<Vec<_>>()
+			.join(",");
+		format!(
+			"if rows == 0 && conn.query_row(\"SELECT EXISTS(SELECT 1 FROM {tab_name} WHERE {pk})\", ::rusqlite::params![{pk_params}], |row| row.get::<_, bool>(0))? {{ return Err(::rusqlite::Error::QueryReturnedNoRows); }}"
+		)
+	};
+	let rows_check = format!(
+		"{stale_check} if rows > 1 {{ return Err(::rusqlite::Error::StatementChangedRows(rows)); }} Ok(rows == 1)"
+	);
+
 	if !(pk.is_empty() || columns.is_empty()) {
-		generator
-			.generate_impl()
+		if tab_attr.update_columns {
+			crate::util::update_fn_check(&columns, &["columns"], "_sync")?;
+		}
+		let mut gen_impl = generator.generate_impl();
+		gen_impl
 			.generate_fn("update_sync")
 			.with_self_arg(FnSelfArg::RefSelf)
 			.with_arg("conn", "&::rusqlite::Connection")
 			.with_return_type("Result<bool, ::rusqlite::Error>")
 			.make_pub()
 			.body(|fn_body| {
-				let mut s = String::new();
-				// ToDo: Hier wie bei Update Probleme beheben
-				s.push_str(&format!("assert!(self.{pk} > 0);"));
-				s.push_str(&exec(&tab_name, &pk, &columns));
-				s.push_str("assert!(rows < 2); Ok(rows == 1)");
-				fn_body.push_parsed(s)?;
+				fn_body.push_parsed(format!(
+					"{pk_check} {} {rows_check}",
+					exec(&tab_name, &pk, &keys.pk, &columns, ver)
+				))?;
 				Ok(())
 			})?;
+		for col in &columns {
+			gen_impl
+				.generate_fn(format!("update_{}_sync", col.field_name()))
+				.with_self_arg(FnSelfArg::RefSelf)
+				.with_arg("conn", "&::rusqlite::Connection")
+				.with_return_type("Result<bool, ::rusqlite::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					fn_body.push_parsed(format!(
+						"{pk_check} {} {rows_check}",
+						exec(&tab_name, &pk, &keys.pk, &[col], ver)
+					))?;
+					Ok(())
+				})?;
+		}
+		if tab_attr.update_columns {
+			let enum_name = crate::util::col_enum_name(&target_name);
+			let col_check = crate::util::not_updatable_check(
+				&cols,
+				&keys,
+				&enum_name,
+				"::rusqlite::Error::InvalidColumnName(msg)",
+			);
+			let where_clause = match ver {
+				None => format!(" WHERE {pk}"),
+				Some(ver) => format!(",{0}={0}+1 WHERE {pk} AND {0}=?", ver.ident()),
+			};
+			let pk_push: String = keys
+				.pk
+				.iter()
+				.map(|c| format!("params.push(&self.{});", c.field))
+				.collect();
+			let n_where = keys.pk.len() + usize::from(ver.is_some());
+			let ver_param = match ver {
+				None => String::new(),
+				Some(ver) => format!("params.push(&self.{});", ver.field),
+			};
+			gen_impl
+				.generate_fn("update_columns_sync")
+				.with_self_arg(FnSelfArg::RefSelf)
+				.with_arg("cols", format!("&[{enum_name}]"))
+				.with_arg("conn", "&::rusqlite::Connection")
+				.with_return_type("Result<bool, ::rusqlite::Error>")
+				.make_pub()
+				.body(|fn_body| {
+					let mut s = format!(
+						"{pk_check} {col_check} if cols.is_empty() {{ return Ok(false); }} let mut sql = String::from(\"UPDATE {tab_name} SET \"); let mut params: Vec<&dyn ::rusqlite::ToSql> = Vec::with_capacity(cols.len() + {n_where}); for c in cols {{ sql.push('\\\"'); sql.push_str(c.name()); sql.push_str(\"\\\"=?,\"); params.push(match c {{"
+					);
+					for c in cols.iter().filter(|c| !c.is_flatten()) {
+						s.push_str(&format!(
+							"{enum_name}::{} => &self.{},",
+							crate::util::col_variant(c.field_name()),
+							c.field
+						));
+					}
+					s.push_str(&format!(
+						"}}); }} sql.pop(); sql.push_str(\"{where_clause}\"); {pk_push} {ver_param} let mut stmt = conn.prepare_cached(&sql)?; let rows = stmt.execute(params.as_slice())?; {rows_check}"
+					));
+					fn_body.push_parsed(s)?;
+					Ok(())
+				})?;
+		}
 	}
 
 	Ok(())
@@ -1,6 +1,10 @@
 use convert_case::{Boundary, Case, Casing};
 use virtue::{
-	prelude::{Error, FromAttribute, Group, Literal, Result},
+	parse::{Attribute, EnumBody, UnnamedField},
+	prelude::{
+		AttributeAccess, Delimiter, Error, FromAttribute, Group, Ident, Literal, Parse, Result, Span,
+		TokenTree,
+	},
 	utils::{ParsedAttribute, parse_tagged_attribute},
 };
 
@@ -18,55 +22,1680 @@ pub(crate) fn tab_name(ident: &str) -> String {
 		.to_case(Case::Snake)
 }
 
+/// Table name: struct attribute name or tab_name of the struct ident.
+pub(crate) fn table_name(ident: &str, tab_attr: &TabAttr) -> String {
+	if tab_attr.name.is_empty() {
+		tab_name(ident)
+	} else {
+		tab_attr.name.clone()
+	}
+}
+
+/// Table name as SQL identifier, see [quote].
+pub(crate) fn table_ident(ident: &str, tab_attr: &TabAttr) -> String {
+	quote(&table_name(ident, tab_attr), tab_attr.quote_all)
+}
+
+/// SQLite keywords <https://www.sqlite.org/lang_keywords.html>
+const KEYWORDS: [&str; 147] = [
+	"ABORT",
+	"ACTION",
+	"ADD",
+	"AFTER",
+	"ALL",
+	"ALTER",
+	"ALWAYS",
+	"ANALYZE",
+	"AND",
+	"AS",
+	"ASC",
+	"ATTACH",
+	"AUTOINCREMENT",
+	"BEFORE",
+	"BEGIN",
+	"BETWEEN",
+	"BY",
+	"CASCADE",
+	"CASE",
+	"CAST",
+	"CHECK",
+	"COLLATE",
+	"COLUMN",
+	"COMMIT",
+	"CONFLICT",
+	"CONSTRAINT",
+	"CREATE",
+	"CROSS",
+	"CURRENT",
+	"CURRENT_DATE",
+	"CURRENT_TIME",
+	"CURRENT_TIMESTAMP",
+	"DATABASE",
+	"DEFAULT",
+	"DEFERRABLE",
+	"DEFERRED",
+	"DELETE",
+	"DESC",
+	"DETACH",
+	"DISTINCT",
+	"DO",
+	"DROP",
+	"EACH",
+	"ELSE",
+	"END",
+	"ESCAPE",
+	"EXCEPT",
+	"EXCLUDE",
+	"EXCLUSIVE",
+	"EXISTS",
+	"EXPLAIN",
+	"FAIL",
+	"FILTER",
+	"FIRST",
+	"FOLLOWING",
+	"FOR",
+	"FOREIGN",
+	"FROM",
+	"FULL",
+	"GENERATED",
+	"GLOB",
+	"GROUP",
+	"GROUPS",
+	"HAVING",
+	"IF",
+	"IGNORE",
+	"IMMEDIATE",
+	"IN",
+	"INDEX",
+	"INDEXED",
+	"INITIALLY",
+	"INNER",
+	"INSERT",
+	"INSTEAD",
+	"INTERSECT",
+	"INTO",
+	"IS",
+	"ISNULL",
+	"JOIN",
+	"KEY",
+	"LAST",
+	"LEFT",
+	"LIKE",
+	"LIMIT",
+	"MATCH",
+	"MATERIALIZED",
+	"NATURAL",
+	"NO",
+	"NOT",
+	"NOTHING",
+	"NOTNULL",
+	"NULL",
+	"NULLS",
+	"OF",
+	"OFFSET",
+	"ON",
+	"OR",
+	"ORDER",
+	"OTHERS",
+	"OUTER",
+	"OVER",
+	"PARTITION",
+	"PLAN",
+	"PRAGMA",
+	"PRECEDING",
+	"PRIMARY",
+	"QUERY",
+	"RAISE",
+	"RANGE",
+	"RECURSIVE",
+	"REFERENCES",
+	"REGEXP",
+	"REINDEX",
+	"RELEASE",
+	"RENAME",
+	"REPLACE",
+	"RESTRICT",
+	"RETURNING",
+	"RIGHT",
+	"ROLLBACK",
+	"ROW",
+	"ROWS",
+	"SAVEPOINT",
+	"SELECT",
+	"SET",
+	"TABLE",
+	"TEMP",
+	"TEMPORARY",
+	"THEN",
+	"TIES",
+	"TO",
+	"TRANSACTION",
+	"TRIGGER",
+	"UNBOUNDED",
+	"UNION",
+	"UNIQUE",
+	"UPDATE",
+	"USING",
+	"VACUUM",
+	"VALUES",
+	"VIEW",
+	"VIRTUAL",
+	"WHEN",
+	"WHERE",
+	"WINDOW",
+	"WITH",
+	"WITHOUT",
+];
+
+/// SQL identifier: double-quoted if quote_all, a SQLite keyword or not a plain identifier.
+///
+/// The quotes are escaped, the identifier is emitted inside a rust string literal.
+pub(crate) fn quote(name: &str, quote_all: bool) -> String {
+	let plain = name
+		.chars()
+		.next()
+		.is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+		&& name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+	if quote_all || !plain || KEYWORDS.contains(&name.to_ascii_uppercase().as_str()) {
+		format!("\\\"{}\\\"", name.replace('"', "\\\"\\\""))
+	} else {
+		name.to_string()
+	}
+}
+
+/// Case of struct attribute rename_all, serde naming.
+fn rename_case(rename_all: &str) -> Option<Case<'static>> {
+	match rename_all {
+		"lowercase" => Some(Case::Flat),
+		"UPPERCASE" => Some(Case::UpperFlat),
+		"PascalCase" => Some(Case::Pascal),
+		"camelCase" => Some(Case::Camel),
+		"snake_case" => Some(Case::Snake),
+		"SCREAMING_SNAKE_CASE" => Some(Case::UpperSnake),
+		_ => None,
+	}
+}
+
+/// Name of the column identifier enum: TypeName -> TypeNameColumn
+pub(crate) fn col_enum_name(ident: &str) -> String {
+	format!("{ident}Column")
+}
+
+/// Variant of the column identifier enum: field_name -> FieldName
+pub(crate) fn col_variant(field_name: &str) -> String {
+	field_name.to_case(Case::Pascal)
+}
+
 /// Get SQLite column datatype from given rust type-string.
+///
+/// The type-string is normalized by [rust_typ], `Option<T>` is nullable.\
+/// The types of the crates chrono, time, uuid and serde_json are mapped with their cargo feature.
 pub(crate) fn col_typ(rust_typ: &str) -> &'static str {
-	match rust_typ {
-		"bool" | "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" => "INTEGER NOT NULL",
-		"f32" | "f64" => "REAL NOT NULL",
-		"&str" | "String" => "TEXT NOT NULL",
-		"&[u8]" | "Vec<u8>" => "BLOB NOT NULL",
-		"Option<bool>" | "Option<i8>" | "Option<i16>" | "Option<i32>" | "Option<i64>"
-		| "Option<u8>" | "Option<u16>" | "Option<u32>" => "INTEGER",
-		"Option<f32>" | "Option<f64>" => "REAL",
-		"Option<String>" => "TEXT",
-		"Option<Vec<u8>>" => "BLOB",
-		_ => "ANY",
+	let (typ, path_typ, nullable) = option_typ(rust_typ);
+	let datatype = match typ.as_str() {
+		"bool" | "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" => "INTEGER",
+		"f32" | "f64" => "REAL",
+		"&str" | "String" | "Box<str>" | "Rc<str>" | "Arc<str>" | "Cow<str>" => "TEXT",
+		"&[u8]" | "Vec<u8>" | "Box<[u8]>" | "Cow<[u8]>" => "BLOB",
+		_ => match crate_typ(&typ, &path_typ) {
+			Some(datatype) => datatype,
+			None => return "ANY",
+		},
+	};
+	match (datatype, nullable) {
+		("INTEGER", false) => "INTEGER NOT NULL",
+		("REAL", false) => "REAL NOT NULL",
+		("TEXT", false) => "TEXT NOT NULL",
+		("BLOB", false) => "BLOB NOT NULL",
+		(datatype, _) => datatype,
 	}
 }
 
-#[derive(Debug, Default)]
-pub(crate) struct TabAttr {
-	pub(crate) constraint: String, // table-constraint(s)
-	pub(crate) option: String,     // table-option other than STRICT
+/// Domain of the rust type as CHECK expression after the column name,
+/// for bool and the integer types narrower than i64.
+///
+/// SQLite accepts any i64 in an INTEGER column, the read of 300 into an u8 fails.
+pub(crate) fn typ_check(rust_typ: &str) -> Option<&'static str> {
+	match option_typ(rust_typ).0.as_str() {
+		"bool" => Some("IN (0,1)"),
+		"i8" => Some("BETWEEN -128 AND 127"),
+		"i16" => Some("BETWEEN -32768 AND 32767"),
+		"i32" => Some("BETWEEN -2147483648 AND 2147483647"),
+		"u8" => Some("BETWEEN 0 AND 255"),
+		"u16" => Some("BETWEEN 0 AND 65535"),
+		"u32" => Some("BETWEEN 0 AND 4294967295"),
+		_ => None,
+	}
 }
 
-impl FromAttribute for TabAttr {
-	fn parse(group: &Group) -> Result<Option<Self>> {
-		let Some(attributes) = parse_tagged_attribute(group, "sql")? else {
+/// Datatype of the feature enabled crate types, in the storage format of sqlx and rusqlite.
+///
+/// Date, Time and Value are only known with their crate path: `time::Date`, `serde_json::Value`.
+fn crate_typ(typ: &str, path_typ: &str) -> Option<&'static str> {
+	let path_typ = path_typ.trim_start_matches("::");
+	match typ {
+		#[cfg(feature = "uuid")]
+		"Uuid" => Some("BLOB"),
+		#[cfg(feature = "chrono")]
+		"NaiveDate" | "NaiveDateTime" | "NaiveTime" => Some("TEXT"),
+		#[cfg(feature = "chrono")]
+		t if t.starts_with("DateTime<") => Some("TEXT"),
+		#[cfg(feature = "time")]
+		"OffsetDateTime" | "PrimitiveDateTime" => Some("TEXT"),
+		#[cfg(feature = "time")]
+		"Date" | "Time" if path_typ.starts_with("time::") => Some("TEXT"),
+		#[cfg(feature = "rust_decimal")]
+		"Decimal" => Some("TEXT"),
+		_ if json(typ, path_typ) => Some("TEXT"),
+		_ => None,
+	}
+}
+
+/// JSON stored as TEXT, checked with `json_valid`: serde_json::Value and the sqlx types JsonValue, Json<T>.
+pub(crate) fn is_json(rust_typ: &str) -> bool {
+	let (typ, path_typ, _) = option_typ(rust_typ);
+	json(&typ, path_typ.trim_start_matches("::"))
+}
+
+fn json(typ: &str, path_typ: &str) -> bool {
+	cfg!(feature = "serde_json")
+		&& (path_typ == "serde_json::Value" || typ == "JsonValue" || typ.starts_with("Json<"))
+}
+
+/// The type-string without and with paths, the type of `Option<T>` is T and nullable.
+fn option_typ(rust_typ: &str) -> (String, String, bool) {
+	let typ = normalize(rust_typ, false);
+	let path_typ = normalize(rust_typ, true);
+	match (
+		typ.strip_prefix("Option<")
+			.and_then(|t| t.strip_suffix('>')),
+		path_typ.split_once('<'),
+	) {
+		(Some(inner), Some((_, path_inner))) => (
+			inner.to_string(),
+			path_inner
+				.strip_suffix('>')
+				.unwrap_or(path_inner)
+				.to_string(),
+			true,
+		),
+		_ => (typ, path_typ, false),
+	}
+}
+
+/// The struct has type or const generic parameters, lifetimes are elided by [sql_type_path].
+pub(crate) fn has_generics(parse: &Parse) -> bool {
+	let generics = match parse {
+		Parse::Struct { generics, .. } | Parse::Enum { generics, .. } => generics.as_ref(),
+		_ => None,
+	};
+	generics.is_some_and(|g| g.iter_generics().next().is_some() || g.iter_consts().next().is_some())
+}
+
+/// Error for u64, usize, i128 and u128, they exceed the INTEGER range of SQLite.
+pub(crate) fn integer_range(rust_typ: &str, uf: &UnnamedField) -> Result {
+	let inner = option_typ(rust_typ).0;
+	if matches!(inner.as_str(), "u64" | "usize" | "i128" | "u128") {
+		return Err(Error::custom_at(
+			format!(
+				"{inner} can exceed i64::MAX, the INTEGER range of SQLite, the bind or read of a larger value fails: use i64, or set typ to store it anyway"
+			),
+			uf.span(),
+		));
+	}
+	Ok(())
+}
+
+/// [integer_range] of the struct fields without attribute typ or skip.
+///
+/// Checked by the derives which write or create the columns, reading a column is not restricted.
+pub(crate) fn integer_ranges(struct_fields: &[(Ident, UnnamedField)]) -> Result {
+	for (_, uf) in struct_fields {
+		let attr = uf
+			.attributes
+			.get_attribute::<ColAttr>()?
+			.unwrap_or_default();
+		if !attr.skip && attr.typ.is_empty() {
+			integer_range(&type_string(uf), uf)?;
+		}
+	}
+	Ok(())
+}
+
+/// Type of the SqlType consts in the generated code, the type of `Option<T>` is T and nullable.
+///
+/// Lifetimes other than 'static are elided, the consts are read outside of the struct.
+pub(crate) fn sql_type_path(rust_typ: &str) -> (String, bool) {
+	let mut typ = String::with_capacity(rust_typ.len());
+	let mut chars = rust_typ.chars().peekable();
+	while let Some(c) = chars.next() {
+		typ.push(c);
+		if c == '\'' {
+			let mut lifetime = String::new();
+			while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+				lifetime.push(c);
+			}
+			if lifetime == "static" {
+				typ.push_str("static");
+			} else {
+				typ.push('_');
+			}
+		}
+	}
+	let nullable = option_typ(rust_typ).2;
+	if nullable {
+		if let (Some(start), Some(end)) = (typ.find('<'), typ.rfind('>')) {
+			typ = typ[start + 1..end].trim().to_string();
+		}
+	}
+	(typ, nullable)
+}
+
+/// Rust type-string of a field, `&'a str` is kept apart unlike `UnnamedField::type_string`.
+pub(crate) fn type_string(uf: &UnnamedField) -> String {
+	let mut typ = String::new();
+	let mut after_ident = false;
+	for t in &uf.r#type {
+		let is_ident = matches!(t, TokenTree::Ident(_));
+		if after_ident && is_ident {
+			typ.push(' ');
+		}
+		typ.push_str(&t.to_string());
+		after_ident = is_ident;
+	}
+	typ
+}
+
+/// Normalize a rust type-string: without whitespace, lifetimes and paths.
+///
+/// `std::option::Option<&'a std::primitive::str>` -> `Option<&str>`
+pub(crate) fn rust_typ(rust_typ: &str) -> String {
+	normalize(rust_typ, false)
+}
+
+fn normalize(rust_typ: &str, paths: bool) -> String {
+	let mut typ = String::with_capacity(rust_typ.len());
+	let mut chars = rust_typ.chars().peekable();
+	while let Some(c) = chars.next() {
+		if c.is_whitespace() {
+			continue;
+		} else if c == '\'' {
+			// lifetime 'a, as generic argument with its ','
+			while chars
+				.next_if(|c| c.is_alphanumeric() || *c == '_')
+				.is_some()
+			{}
+			while chars.next_if(|c| c.is_whitespace()).is_some() {}
+			if typ.ends_with('<') {
+				chars.next_if_eq(&',');
+			}
+		} else if !paths && c == ':' && chars.next_if_eq(&':').is_some() {
+			// path segment
+			let start = typ
+				.rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+				.map_or(0, |i| i + 1);
+			typ.truncate(start);
+		} else {
+			typ.push(c);
+		}
+	}
+	typ
+}
+
+/// Column discovery: struct field -> table column
+#[derive(Debug)]
+pub(crate) struct Col {
+	pub(crate) field: String, // field-name, may be a raw identifier r#name
+	pub(crate) name: String,  // column-name: attribute name, rename_all or field-name
+	pub(crate) typ: String,   // rust type-string
+	pub(crate) attr: ColAttr,
+	quote_all: bool, // struct attribute quote_all
+}
+
+impl Col {
+	pub(crate) fn is_pk(&self) -> bool {
+		self.attr.is_pk()
+	}
+
+	pub(crate) fn is_unique(&self) -> bool {
+		self.attr.is_unique()
+	}
+
+	/// Rust type is i64, the rowid alias of an INTEGER PRIMARY KEY.
+	pub(crate) fn is_i64(&self) -> bool {
+		rust_typ(&self.typ) == "i64"
+	}
+
+	/// Field with attribute flatten, the name is the column-name prefix.
+	pub(crate) fn is_flatten(&self) -> bool {
+		self.attr.flatten
+	}
+
+	/// Field-name without the raw identifier prefix r#, used in SQL and fn names.
+	pub(crate) fn field_name(&self) -> &str {
+		self.field.trim_start_matches("r#")
+	}
+
+	/// Column name as SQL identifier, see [quote].
+	pub(crate) fn ident(&self) -> String {
+		quote(&self.name, self.quote_all)
+	}
+
+	/// Result column for SELECT, aliased to the field-name if renamed.
+	pub(crate) fn select_expr(&self) -> String {
+		if self.name == self.field_name() {
+			self.ident()
+		} else {
+			format!(
+				"{} AS {}",
+				self.ident(),
+				quote(self.field_name(), self.quote_all)
+			)
+		}
+	}
+
+	/// Rust type for a fn argument taking a value of this column.
+	pub(crate) fn arg_typ(&self) -> &str {
+		if self.typ == "String" {
+			"&str"
+		} else {
+			&self.typ
+		}
+	}
+}
+
+/// Get the columns of all struct fields, except the fields with attribute skip.
+///
+/// The column name is the field attribute name, else the field-name converted by rename_all.\
+/// There can be only one version column and it must not be the PRIMARY KEY.
+pub(crate) fn cols(
+	struct_fields: Vec<(Ident, UnnamedField)>,
+	tab_attr: &TabAttr,
+) -> Result<Vec<Col>> {
+	let rename = rename_case(&tab_attr.rename_all);
+	let mut cols: Vec<Col> = Vec::with_capacity(struct_fields.len());
+	for (ident, uf) in struct_fields {
+		let field = ident.to_string();
+		let field_name = field.trim_start_matches("r#");
+		let attr = uf
+			.attributes
+			.get_attribute::<ColAttr>()?
+			.unwrap_or_default();
+		if attr.skip {
+			continue;
+		}
+		let name = if let Some(prefix) = &attr.prefix {
+			prefix.clone()
+		} else if attr.flatten {
+			format!("{field_name}_")
+		} else if !attr.name.is_empty() {
+			attr.name.clone()
+		} else if let Some(case) = rename {
+			field_name.to_case(case)
+		} else {
+			field_name.to_string()
+		};
+		let typ = type_string(&uf);
+		let col = Col {
+			field,
+			name,
+			typ,
+			attr,
+			quote_all: tab_attr.quote_all,
+		};
+		if col.attr.version && (col.is_pk() || cols.iter().any(|c| c.attr.version)) {
+			return Err(Error::custom_at(
+				"only one version column is allowed and it can not be the PRIMARY KEY",
+				ident.span(),
+			));
+		}
+		cols.push(col);
+	}
+	Ok(cols)
+}
+
+/// Get the columns of a struct which is flattened into another struct.
+///
+/// The columns are written and read as part of the outer row,
+/// a version, readonly, generated, PRIMARY KEY or nested flatten column has no meaning there.
+pub(crate) fn flatten_cols(
+	struct_fields: Vec<(Ident, UnnamedField)>,
+	tab_attr: &TabAttr,
+) -> Result<Vec<Col>> {
+	let spans: Vec<_> = struct_fields
+		.iter()
+		.map(|(ident, _)| (ident.to_string(), ident.span()))
+		.collect();
+	let cols = cols(struct_fields, tab_attr)?;
+	for col in &cols {
+		if col.attr.version || col.attr.is_readonly() || col.is_pk() || col.is_flatten() {
+			let msg =
+				"a flattened struct can not have version, readonly, generated, pk or flatten fields";
+			return Err(
+				spans
+					.iter()
+					.find(|(field, _)| *field == col.field)
+					.map_or_else(
+						|| Error::custom(msg),
+						|(_, span)| Error::custom_at(msg, *span),
+					),
+			);
+		}
+	}
+	Ok(cols)
+}
+
+/// Compile error for a field whose update fn has the name of another generated fn,
+/// `taken` are these field-names.
+pub(crate) fn update_fn_check(columns: &[&Col], taken: &[&str], suffix: &str) -> Result {
+	for col in columns {
+		let name = col.field_name();
+		if taken.contains(&name) {
+			return Err(Error::custom(format!(
+				"field {name}: update_{name}{suffix} collides with the generated fn of the same name, rename the field"
+			)));
+		}
+	}
+	Ok(())
+}
+
+/// Statement for update_columns to return `err` for a PRIMARY KEY, version or readonly column
+/// in the given `cols` slice, `err` is an expression of the message `msg`.
+pub(crate) fn not_updatable_check(cols: &[Col], keys: &Keys, enum_name: &str, err: &str) -> String {
+	let fixed: Vec<String> = cols
+		.iter()
+		.filter(|c| keys.is_pk(c) || c.attr.version || c.attr.is_readonly())
+		.map(|c| format!("{enum_name}::{}", col_variant(c.field_name())))
+		.collect();
+	if fixed.is_empty() {
+		String::new()
+	} else {
+		format!(
+			"if let Some(c) = cols.iter().find(|c| matches!(c, {})) {{ let msg = format!(\"{{}} is not updatable\", c.name()); return Err({err}); }}",
+			fixed.join(" | ")
+		)
+	}
+}
+
+/// Field-names with attribute skip, they are filled with Default when reading rows.
+pub(crate) fn skipped_fields(struct_fields: &[(Ident, UnnamedField)]) -> Result<Vec<String>> {
+	let mut skipped = Vec::new();
+	for (ident, uf) in struct_fields {
+		if uf
+			.attributes
+			.get_attribute::<ColAttr>()?
+			.is_some_and(|attr| attr.skip)
+		{
+			skipped.push(ident.to_string());
+		}
+	}
+	Ok(skipped)
+}
+
+/// The version column
+pub(crate) fn version_col(cols: &[Col]) -> Option<&Col> {
+	cols.iter().find(|c| c.attr.version)
+}
+
+/// Result columns for SELECT: `a,b AS c`, the columns of a flattened field are not aliased.
+pub(crate) fn select_list(cols: &[Col]) -> Sql {
+	let mut sql = Sql::default();
+	for (i, col) in cols.iter().enumerate() {
+		if i > 0 {
+			sql.push_str(",");
+		}
+		if col.is_flatten() {
+			sql.push_flatten(col, ("", "", ","), FLAT_NAME, "");
+		} else {
+			sql.push_str(&col.select_expr());
+		}
+	}
+	sql
+}
+
+/// SQL text with the columns of flattened fields and the datatypes of derive SqlType.
+///
+/// The columns of a flattened field are the consts of its derive FlattenSql,
+/// they are rendered by const evaluation in the generated code.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Sql {
+	parts: Vec<SqlPart>,
+}
+
+/// Literal SQL or a const expression of SQL, followed by the columns of a flattened field.
+#[derive(Clone, Debug)]
+struct SqlPart {
+	lit: String,
+	is_const: bool,
+	flatten: Option<FlatCols>,
+}
+
+/// Each column of a flattened field is rendered as
+/// `{before}{prefix}{column}{after}[{prefix}{column}][ {def}][ {check}]`, separated by sep.\
+/// The checks of `FLATTEN_COL_DEFS` come from `FLATTEN_COL_CHECKS`, `?` is the prefixed column.
+#[derive(Clone, Debug)]
+struct FlatCols {
+	typ: String,
+	prefix: String,
+	defs: &'static str,
+	before: String,
+	after: String,
+	sep: &'static str,
+	flags: u8,
+}
+
+// FlatCols flags, see SQL_RENDER
+const FLAT_NAME: u8 = 1;
+const FLAT_TWICE: u8 = 2;
+const FLAT_DEF: u8 = 4;
+const FLAT_QUOTE: u8 = 8;
+
+/// Trait of the generated code for the consts of a type without derive SqlType.
+///
+/// An inherent const of derive SqlType takes precedence over the trait const.
+pub(crate) const SQL_TYPE_ANY: &str = "#[allow(dead_code)] trait SqlTypeAny { const SQL_TYPE: &'static str = \"ANY\"; const NULLABLE: bool = true; } impl<T: ?Sized> SqlTypeAny for T {}";
+
+/// const fn of the generated code to render the parts of [Sql].
+const SQL_RENDER: &str = "const fn render(buf: &mut [u8], parts: &[(&str, &[&str], &[&str], &[&str], &str, &str, &str, &str, u8)]) -> usize {
+	const fn put(buf: &mut [u8], mut n: usize, s: &str) -> usize {
+		let s = s.as_bytes();
+		let mut i = 0;
+		while i < s.len() {
+			if n < buf.len() {
+				buf[n] = s[i];
+			}
+			n += 1;
+			i += 1;
+		}
+		n
+	}
+	const fn name(buf: &mut [u8], mut n: usize, prefix: &str, col: &str, flags: u8) -> usize {
+		if flags & 8 != 0 {
+			n = put(buf, n, \"\\\"\");
+		}
+		n = put(buf, n, prefix);
+		n = put(buf, n, col);
+		if flags & 8 != 0 {
+			n = put(buf, n, \"\\\"\");
+		}
+		n
+	}
+	let mut n = 0;
+	let mut p = 0;
+	while p < parts.len() {
+		let (lit, cols, defs, checks, before, prefix, after, sep, flags) = parts[p];
+		n = put(buf, n, lit);
+		let mut i = 0;
+		while i < cols.len() {
+			if i > 0 {
+				n = put(buf, n, sep);
+			}
+			n = put(buf, n, before);
+			if flags & 1 != 0 {
+				n = name(buf, n, prefix, cols[i], flags);
+			}
+			n = put(buf, n, after);
+			if flags & 2 != 0 {
+				n = name(buf, n, prefix, cols[i], flags);
+			}
+			if flags & 4 != 0 {
+				n = put(buf, n, \" \");
+				n = put(buf, n, defs[i]);
+			}
+			if i < checks.len() && !checks[i].is_empty() {
+				n = put(buf, n, \" \");
+				let check = checks[i].as_bytes();
+				let mut j = 0;
+				while j < check.len() {
+					if check[j] == b'?' {
+						n = name(buf, n, prefix, cols[i], flags);
+					} else {
+						if n < buf.len() {
+							buf[n] = check[j];
+						}
+						n += 1;
+					}
+					j += 1;
+				}
+			}
+			i += 1;
+		}
+		p += 1;
+	}
+	n
+}";
+
+impl Sql {
+	pub(crate) fn push_str(&mut self, s: &str) {
+		match self.parts.last_mut() {
+			Some(part) if part.flatten.is_none() && !part.is_const => part.lit.push_str(s),
+			_ => self.parts.push(SqlPart {
+				lit: s.to_string(),
+				is_const: false,
+				flatten: None,
+			}),
+		}
+	}
+
+	/// Const &str expression of the generated code.
+	fn push_const(&mut self, expr: &str) {
+		self.parts.push(SqlPart {
+			lit: expr.to_string(),
+			is_const: true,
+			flatten: None,
+		});
+	}
+
+	/// Datatype of the rust type-string, see [col_typ], without NOT NULL if nullable.
+	///
+	/// Other types use the consts SQL_TYPE and NULLABLE of derive SqlType, without it ANY.
+	/// The const block can not name the generic parameters of the struct, with them it is ANY.
+	pub(crate) fn push_col_typ(&mut self, rust_typ: &str, nullable: bool, generic: bool) {
+		let datatype = col_typ(rust_typ);
+		if datatype != "ANY" || generic {
+			if nullable {
+				self.push_str(datatype.trim_end_matches(" NOT NULL"));
+			} else {
+				self.push_str(datatype);
+			}
+			return;
+		}
+		let (typ, is_option) = sql_type_path(rust_typ);
+		self.push_const(&format!("<{typ}>::SQL_TYPE"));
+		if !nullable && !is_option {
+			self.push_const(&format!(
+				"if <{typ}>::NULLABLE {{ \"\" }} else {{ \" NOT NULL\" }}"
+			));
+		}
+	}
+
+	fn push_flat_cols(&mut self, flat_cols: FlatCols) {
+		match self.parts.last_mut() {
+			Some(part) if part.flatten.is_none() => part.flatten = Some(flat_cols),
+			_ => self.parts.push(SqlPart {
+				lit: String::new(),
+				is_const: false,
+				flatten: Some(flat_cols),
+			}),
+		}
+	}
+
+	fn push_flatten(
+		&mut self,
+		col: &Col,
+		(before, after, sep): (&str, &str, &'static str),
+		flags: u8,
+		defs: &'static str,
+	) {
+		let quote = if col.quote_all || col.name.is_empty() {
+			FLAT_QUOTE
+		} else {
+			0
+		};
+		self.push_flat_cols(FlatCols {
+			typ: col.typ.clone(),
+			prefix: col.name.clone(),
+			defs,
+			before: before.to_string(),
+			after: after.to_string(),
+			sep,
+			flags: flags | quote,
+		});
+	}
+
+	pub(crate) fn push_sql(&mut self, sql: Sql) {
+		for part in sql.parts {
+			if part.is_const {
+				self.push_const(&part.lit);
+			} else {
+				self.push_str(&part.lit);
+			}
+			if let Some(flat_cols) = part.flatten {
+				self.push_flat_cols(flat_cols);
+			}
+		}
+	}
+
+	/// Columns separated by sep: `{before}{col}{after}`
+	pub(crate) fn push_cols(&mut self, cols: &[&Col], before: &str, after: &str, sep: &'static str) {
+		for (i, col) in cols.iter().enumerate() {
+			if i > 0 {
+				self.push_str(sep);
+			}
+			if col.is_flatten() {
+				self.push_flatten(col, (before, after, sep), FLAT_NAME, "");
+			} else {
+				self.push_str(before);
+				self.push_str(&col.ident());
+				self.push_str(after);
+			}
+		}
+	}
+
+	/// Upsert SET for the columns: `{col}=excluded.{col}`
+	pub(crate) fn push_cols_excluded(&mut self, cols: &[&Col]) {
+		for (i, col) in cols.iter().enumerate() {
+			if i > 0 {
+				self.push_str(",");
+			}
+			if col.is_flatten() {
+				self.push_flatten(col, ("", "=excluded.", ","), FLAT_NAME | FLAT_TWICE, "");
+			} else {
+				self.push_str(&format!("{0}=excluded.{0}", col.ident()));
+			}
+		}
+	}
+
+	/// Trigger condition that a column changed: `OLD.{col} IS NOT NEW.{col} OR ...`
+	pub(crate) fn push_cols_changed(&mut self, cols: &[&Col]) {
+		for (i, col) in cols.iter().enumerate() {
+			if i > 0 {
+				self.push_str(" OR ");
+			}
+			if col.is_flatten() {
+				self.push_flatten(
+					col,
+					("OLD.", " IS NOT NEW.", " OR "),
+					FLAT_NAME | FLAT_TWICE,
+					"",
+				);
+			} else {
+				self.push_str(&format!("OLD.{0} IS NOT NEW.{0}", col.ident()));
+			}
+		}
+	}
+
+	/// Bind parameters for the columns: `?,?`
+	pub(crate) fn push_values(&mut self, cols: &[&Col]) {
+		for (i, col) in cols.iter().enumerate() {
+			if i > 0 {
+				self.push_str(",");
+			}
+			if col.is_flatten() {
+				self.push_flatten(col, ("?", "", ","), 0, "");
+			} else {
+				self.push_str("?");
+			}
+		}
+	}
+
+	/// Column definitions of a flattened field: `{col} {def}`, with constraints or only the datatype.
+	pub(crate) fn push_flatten_defs(&mut self, col: &Col, constraints: bool) {
+		let defs = if constraints {
+			"FLATTEN_COL_DEFS"
+		} else {
+			"FLATTEN_COL_TYPES"
+		};
+		self.push_flatten(col, ("", "", ", "), FLAT_NAME | FLAT_DEF, defs);
+	}
+
+	pub(crate) fn is_empty(&self) -> bool {
+		self
+			.parts
+			.iter()
+			.all(|part| part.lit.is_empty() && part.flatten.is_none())
+	}
+
+	/// Rust expression of the SQL as &'static str.
+	///
+	/// A string literal, or a const block if there are flattened fields or SqlType datatypes.
+	pub(crate) fn expr(&self) -> String {
+		if self
+			.parts
+			.iter()
+			.all(|part| part.flatten.is_none() && !part.is_const)
+		{
+			let lit: String = self.parts.iter().map(|part| part.lit.as_str()).collect();
+			return format!("\"{lit}\"");
+		}
+		let sql_type_any = if self.parts.iter().any(|part| part.is_const) {
+			SQL_TYPE_ANY
+		} else {
+			""
+		};
+		let mut parts = String::new();
+		for part in &self.parts {
+			let lit = if part.is_const {
+				part.lit.clone()
+			} else {
+				format!("\"{}\"", part.lit)
+			};
+			match &part.flatten {
+				None => parts.push_str(&format!(
+					"({lit}, &[], &[], &[], \"\", \"\", \"\", \"\", 0),"
+				)),
+				Some(f) => {
+					let defs = if f.defs.is_empty() {
+						"&[]".to_string()
+					} else {
+						format!("<{}>::{}", f.typ, f.defs)
+					};
+					// the checks belong to the column definitions with constraints
+					let checks = if f.defs == "FLATTEN_COL_DEFS" {
+						format!("<{}>::FLATTEN_COL_CHECKS", f.typ)
+					} else {
+						"&[]".to_string()
+					};
+					parts.push_str(&format!(
+						"({lit}, <{}>::FLATTEN_COLUMNS, {defs}, {checks}, \"{}\", \"{}\", \"{}\", \"{}\", {}),",
+						f.typ, f.before, f.prefix, f.after, f.sep, f.flags
+					));
+				}
+			}
+		}
+		format!(
+			"{{ const SQL: &str = {{ {sql_type_any} const PARTS: &[(&str, &[&str], &[&str], &[&str], &str, &str, &str, &str, u8)] = &[{parts}]; {SQL_RENDER} const LEN: usize = render(&mut [], PARTS); const BUF: [u8; LEN] = {{ let mut buf = [0; LEN]; render(&mut buf, PARTS); buf }}; match ::core::str::from_utf8(&BUF) {{ Ok(s) => s, Err(_) => panic!(\"invalid utf-8\") }} }}; SQL }}"
+		)
+	}
+}
+
+/// `INSERT INTO {tab_name} ({columns},{ver}) VALUES (?,0){upsert}`
+///
+/// The version column `ver` (empty for none) starts at 0.
+pub(crate) fn insert_sql(tab_name: &str, columns: &[&Col], ver: &str, upsert: Option<&Sql>) -> Sql {
+	let mut sql = Sql::default();
+	sql.push_str(&format!("INSERT INTO {tab_name} ("));
+	sql.push_cols(columns, "", "", ",");
+	let sep = if columns.is_empty() { "" } else { "," };
+	if !ver.is_empty() {
+		sql.push_str(&format!("{sep}{ver}"));
+	}
+	sql.push_str(") VALUES (");
+	sql.push_values(columns);
+	if !ver.is_empty() {
+		sql.push_str(&format!("{sep}0"));
+	}
+	sql.push_str(")");
+	if let Some(upsert) = upsert {
+		sql.push_sql(upsert.clone());
+	}
+	sql
+}
+
+/// `UPDATE {tab_name} SET {columns}=?,{ver}={ver}+1 WHERE {pk} AND {ver}=?`
+pub(crate) fn update_sql(tab_name: &str, columns: &[&Col], pk: &str, ver: Option<&Col>) -> Sql {
+	let mut sql = Sql::default();
+	sql.push_str(&format!("UPDATE {tab_name} SET "));
+	sql.push_cols(columns, "", "=?", ",");
+	match ver {
+		None => sql.push_str(&format!(" WHERE {pk}")),
+		Some(ver) => sql.push_str(&format!(",{0}={0}+1 WHERE {pk} AND {0}=?", ver.ident())),
+	}
+	sql
+}
+
+/// Table keys: the PRIMARY KEY and all UNIQUE keys, each key is a list of columns.
+#[derive(Debug)]
+pub(crate) struct Keys<'a> {
+	pub(crate) pk: Vec<&'a Col>,
+	pub(crate) unique: Vec<Vec<&'a Col>>,
+}
+
+impl Keys<'_> {
+	/// The single PRIMARY KEY column, None for no or a composite PRIMARY KEY.
+	pub(crate) fn single_pk(&self) -> Option<&Col> {
+		match self.pk.as_slice() {
+			[pk] => Some(pk),
+			_ => None,
+		}
+	}
+
+	/// Is the column part of the PRIMARY KEY
+	pub(crate) fn is_pk(&self, col: &Col) -> bool {
+		self.pk.iter().any(|c| c.name == col.name)
+	}
+
+	/// PRIMARY KEY followed by the UNIQUE keys
+	pub(crate) fn all(&self) -> impl Iterator<Item = &Vec<&Col>> {
+		std::iter::once(&self.pk)
+			.filter(|pk| !pk.is_empty())
+			.chain(self.unique.iter())
+	}
+
+	/// Keys for get_by fn's, a single column key with attribute skip_get is left out.
+	pub(crate) fn get_keys(&self) -> Vec<&Vec<&Col>> {
+		self
+			.all()
+			.filter(|key| !matches!(key.as_slice(), [c] if c.attr.skip_get))
+			.collect()
+	}
+}
+
+/// Get the keys from the column constraints and the table constraint.
+///
+/// Column constraint PRIMARY KEY / UNIQUE and table constraint PRIMARY KEY(a,b) / UNIQUE(a,b).\
+/// Detection works only if the constraint is in all caps.
+pub(crate) fn keys<'a>(cols: &'a [Col], tab_constraint: &str) -> Result<Keys<'a>> {
+	let find = |names: Vec<String>| -> Result<Vec<&'a Col>> {
+		let mut key = Vec::with_capacity(names.len());
+		for name in names {
+			match cols.iter().find(|c| c.name == name) {
+				Some(col) => key.push(col),
+				None => {
+					return Err(Error::custom(format!(
+						"table constraint column {name} is not a field"
+					)));
+				}
+			}
+		}
+		Ok(key)
+	};
+	let mut pk: Vec<&Col> = cols.iter().filter(|c| c.is_pk()).collect();
+	if pk.is_empty() {
+		if let Some(names) = tab_key_cols(tab_constraint, "PRIMARY KEY")
+			.into_iter()
+			.next()
+		{
+			pk = find(names)?;
+		}
+	}
+	let mut unique: Vec<Vec<&Col>> = cols
+		.iter()
+		.filter(|c| !c.is_pk() && c.is_unique())
+		.map(|c| vec![c])
+		.collect();
+	for names in tab_key_cols(tab_constraint, "UNIQUE") {
+		unique.push(find(names)?);
+	}
+	Ok(Keys { pk, unique })
+}
+
+/// Column names of every `{key}(a,b)` in the table constraint.
+fn tab_key_cols(tab_constraint: &str, key: &str) -> Vec<Vec<String>> {
+	let mut keys = Vec::new();
+	let mut rest = tab_constraint;
+	while let Some(pos) = rest.find(key) {
+		rest = rest[pos + key.len()..].trim_start();
+		let Some(list) = rest.strip_prefix('(') else {
+			continue;
+		};
+		let Some(end) = list.find(')') else {
+			break;
+		};
+		keys.push(
+			list[..end]
+				.split(',')
+				// indexed-column: name [COLLATE ..] [ASC|DESC]
+				.filter_map(|c| c.split_whitespace().next())
+				.map(unquote)
+				.collect(),
+		);
+		rest = &list[end..];
+	}
+	keys
+}
+
+/// Identifier without SQL quotes: `"a"`, `[a]` or `` `a` `` -> `a`
+fn unquote(ident: &str) -> String {
+	ident
+		.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+		.to_string()
+}
+
+/// Comma separated list outside of parentheses: `a, lower(b), substr(c,1,2)` -> `a`, `lower(b)`, `substr(c,1,2)`
+pub(crate) fn split_list(list: &str) -> Vec<&str> {
+	let mut items = Vec::new();
+	let mut depth = 0;
+	let mut start = 0;
+	for (i, c) in list.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth -= 1,
+			',' if depth == 0 => {
+				items.push(list[start..i].trim());
+				start = i + 1;
+			}
+			_ => {}
+		}
+	}
+	items.push(list[start..].trim());
+	items.retain(|item| !item.is_empty());
+	items
+}
+
+/// Part of a generated name from a column or expression: `lower(b)` -> `lower_b`
+pub(crate) fn name_part(expr: &str) -> String {
+	expr
+		.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+		.filter(|part| !part.is_empty())
+		.collect::<Vec<_>>()
+		.join("_")
+}
+
+/// Name of a key for fn names from the field-names: `a_and_b`
+pub(crate) fn key_name(key: &[&Col]) -> String {
+	key.iter()
+		.map(|c| c.field_name())
+		.collect::<Vec<_>>()
+		.join("_and_")
+}
+
+/// SELECT of the log table rows of a key in log order,
+/// with log_audit also the SELECT of the row state at a unix time.
+///
+/// The log rows are the OLD rows of UPDATE / DELETE, a row state ends at its log_at.
+/// So the state at a time is the first later OLD row, else the current row.
+/// A first later NEW row of INSERT means there was no row.
+pub(crate) fn history_sql(
+	target_name: &str,
+	tab_attr: &TabAttr,
+	cols: &[Col],
+	key: &[&Col],
+) -> (Sql, Option<Sql>) {
+	let raw_name = table_name(target_name, tab_attr);
+	let tab_name = quote(&raw_name, tab_attr.quote_all);
+	let tab_log_name = quote(&format!("{raw_name}_log"), tab_attr.quote_all);
+	let key_where = key_where(key);
+	let mut history = Sql::default();
+	history.push_str("SELECT ");
+	history.push_sql(select_list(cols));
+	history.push_str(&format!(
+		" FROM {tab_log_name} WHERE {key_where} ORDER BY rowid"
+	));
+	if !tab_attr.log_audit {
+		return (history, None);
+	}
+	let mut as_of = Sql::default();
+	as_of.push_str("SELECT * FROM (SELECT ");
+	as_of.push_sql(select_list(cols));
+	as_of.push_str(&format!(
+		",0 AS wb_src,rowid AS wb_seq,log_op AS wb_op FROM {tab_log_name} WHERE {key_where} AND log_at>? UNION ALL SELECT "
+	));
+	as_of.push_sql(select_list(cols));
+	as_of.push_str(&format!(
+		",1,0,'C' FROM {tab_name} WHERE {key_where} ORDER BY wb_src,wb_seq LIMIT 1) WHERE wb_op<>'I'"
+	));
+	(history, Some(as_of))
+}
+
+/// Where clause of a key: `a=? AND b=?`
+pub(crate) fn key_where(key: &[&Col]) -> String {
+	key.iter()
+		.map(|c| format!("{}=?", c.ident()))
+		.collect::<Vec<_>>()
+		.join(" AND ")
+}
+
+/// Upsert clause `ON CONFLICT({target}) DO UPDATE SET {col}=excluded.{col},... RETURNING rowid`
+///
+/// The conflict target is the struct attribute `upsert` or the PRIMARY KEY `pk`.\
+/// All columns except the PRIMARY KEY, the conflict target and readonly columns are updated.\
+/// The version column is incremented.\
+/// None if there is no conflict target, nothing to update or no rowid (WITHOUT ROWID),
+/// an error for these cases if the struct attribute `upsert` is set.
+pub(crate) fn upsert_clause(tab_attr: &TabAttr, cols: &[Col], pk: &[&Col]) -> Result<Option<Sql>> {
+	let upsert = tab_attr.upsert.as_str();
+	if tab_attr.option.to_uppercase().contains("WITHOUT ROWID") {
+		if upsert.is_empty() {
 			return Ok(None);
+		}
+		return Err(Error::custom(
+			"upsert returns the rowid, a WITHOUT ROWID table has none",
+		));
+	}
+	let mut target: Vec<&Col> = Vec::new();
+	if upsert.is_empty() {
+		target.extend(pk);
+	} else {
+		for t in upsert.split(',').map(|t| unquote(t.trim())) {
+			match cols.iter().find(|c| c.name == t) {
+				Some(col) if !col.is_flatten() => target.push(col),
+				_ => return Err(Error::custom(format!("upsert target {t} is not a column"))),
+			}
+		}
+	}
+	if target.is_empty() {
+		return Ok(None);
+	}
+	let mut set = Sql::default();
+	for col in cols {
+		if col.attr.version {
+			if !set.is_empty() {
+				set.push_str(",");
+			}
+			set.push_str(&format!("{0}={0}+1", col.ident()));
+		} else if !(col.attr.is_readonly() || pk.iter().chain(&target).any(|c| c.name == col.name)) {
+			if !set.is_empty() {
+				set.push_str(",");
+			}
+			set.push_cols_excluded(&[col]);
+		}
+	}
+	if set.is_empty() {
+		if upsert.is_empty() {
+			return Ok(None);
+		}
+		return Err(Error::custom(format!(
+			"upsert = \"{upsert}\" leaves no column to update"
+		)));
+	}
+	let mut sql = Sql::default();
+	sql.push_str(&format!(
+		" ON CONFLICT({}) DO UPDATE SET ",
+		target
+			.iter()
+			.map(|c| c.ident())
+			.collect::<Vec<_>>()
+			.join(",")
+	));
+	sql.push_sql(set);
+	sql.push_str(" RETURNING rowid");
+	Ok(Some(sql))
+}
+
+/// sqlx binds of the columns `{query}.bind(&self.{field})`, a flattened field binds all its columns.
+pub(crate) fn bind(query: String, cols: &[&Col]) -> String {
+	let mut q = query;
+	for col in cols {
+		q = if col.is_flatten() {
+			format!("self.{}.flatten_bind({q})", col.field)
+		} else {
+			format!("{q}.bind(&self.{})", col.field)
 		};
-		let mut tab = Self::default();
+	}
+	q
+}
+
+/// rusqlite params of the columns `self.{field}` followed by the params of the extra columns.
+///
+/// A flattened field pushes all its columns to a params Vec.
+pub(crate) fn params_sync(cols: &[&Col], extra: &[&Col]) -> String {
+	if cols.iter().any(|c| c.is_flatten()) {
+		let mut s = String::from("{ let mut params: Vec<&dyn ::rusqlite::ToSql> = Vec::new();");
+		for col in cols.iter().chain(extra) {
+			if col.is_flatten() {
+				s.push_str(&format!("self.{}.flatten_params(&mut params);", col.field));
+			} else {
+				s.push_str(&format!("params.push(&self.{});", col.field));
+			}
+		}
+		s.push_str(" params }.as_slice()");
+		s
+	} else {
+		let params = cols
+			.iter()
+			.chain(extra)
+			.map(|c| format!("self.{}", c.field))
+			.collect::<Vec<_>>()
+			.join(",");
+		format!("::rusqlite::params![{params}]")
+	}
+}
+
+/// rusqlite row mapping by column index: `Self { a: row.get(0)?, ... }`
+///
+/// Skipped fields are `Default::default()`.
+///
+/// A flattened field takes the next `FLATTEN_COLUMNS.len()` columns.
+pub(crate) fn row_to_self(cols: &[Col], skipped: &[String]) -> String {
+	let mut s = String::from("Self {");
+	let mut idx = 0;
+	let mut flat_len = String::new();
+	for col in cols {
+		if col.is_flatten() {
+			s.push_str(&format!(
+				"{}: <{1}>::flatten_from_row_sync(row, {idx}{flat_len})?,",
+				col.field, col.typ
+			));
+			flat_len.push_str(&format!(" + <{}>::FLATTEN_COLUMNS.len()", col.typ));
+		} else {
+			s.push_str(&format!("{}: row.get({idx}{flat_len})?,", col.field));
+			idx += 1;
+		}
+	}
+	s.push_str(&default_fields(skipped));
+	s.push('}');
+	s
+}
+
+/// rusqlite row mapping by field-name: `Self { a: row.get("a")?, ... }`
+///
+/// Renamed columns are aliased to the field-name in SELECT_SQL, skipped fields are `Default::default()`.
+pub(crate) fn row_to_self_by_name(cols: &[Col], skipped: &[String]) -> String {
+	let mut s = String::from("Self {");
+	for col in cols {
+		if col.is_flatten() {
+			s.push_str(&format!(
+				"{}: <{}>::flatten_from_row_by_name_sync(row, \"{}\")?,",
+				col.field, col.typ, col.name
+			));
+		} else {
+			s.push_str(&format!(
+				"{}: row.get(\"{}\")?,",
+				col.field,
+				col.field_name()
+			));
+		}
+	}
+	s.push_str(&default_fields(skipped));
+	s.push('}');
+	s
+}
+
+pub(crate) fn default_fields(skipped: &[String]) -> String {
+	skipped
+		.iter()
+		.map(|f| format!("{f}: ::std::default::Default::default(),"))
+		.collect()
+}
+
+/// Get the variant names of a fieldless enum.
+///
+/// Enums with data-carrying variants can not be mapped to a single column.
+pub(crate) fn enum_variants(name: &Ident, enum_body: EnumBody) -> Result<Vec<String>> {
+	let mut variants = Vec::new();
+	for variant in enum_body.variants {
+		if variant.fields.is_some() {
+			return Err(Error::custom_at(
+				"only fieldless enums are supported, variants must not carry data",
+				name.span(),
+			));
+		}
+		variants.push(variant.name.to_string());
+	}
+	if variants.is_empty() {
+		return Err(Error::custom_at(
+			"an enum without variants has no values",
+			name.span(),
+		));
+	}
+	Ok(variants)
+}
+
+/// Error for a derive which has no meaning for an enum.
+pub(crate) fn enum_unsupported(name: &Ident, derive: &str) -> Error {
+	Error::custom_at(
+		format!("{derive} is not supported for enums, use it on a struct"),
+		name.span(),
+	)
+}
+
+/// Error for a derive on an enum, the traits of an enum column come from TextEnum or TextEnumSync.
+pub(crate) fn enum_text(name: &Ident, derive: &str, text_derive: &str) -> Error {
+	Error::custom_at(
+		format!(
+			"{derive} is not supported for enums, derive {text_derive} for a fieldless enum column"
+		),
+		name.span(),
+	)
+}
+
+/// Match expression: enum variant -> variant name as &'static str
+pub(crate) fn enum_to_str(variants: &[String]) -> String {
+	let mut s = String::from("match self {");
+	for v in variants {
+		s.push_str(&format!("Self::{v} => \"{v}\","));
+	}
+	s.push('}');
+	s
+}
+
+/// Match expression: variant name `val` -> `Ok(variant)`, unknown name -> `err`
+pub(crate) fn enum_from_str(variants: &[String], val: &str, err: &str) -> String {
+	let mut s = format!("match {val} {{");
+	for v in variants {
+		s.push_str(&format!("\"{v}\" => Ok(Self::{v}),"));
+	}
+	s.push_str(&format!("_ => {err},}}"));
+	s
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TabAttr {
+	pub(crate) name: String,         // table-name
+	pub(crate) rename_all: String,   // case of the column-names
+	pub(crate) constraint: String,   // table-constraint(s)
+	pub(crate) option: String,       // table-option other than STRICT
+	pub(crate) upsert: String,       // upsert conflict target column(s)
+	pub(crate) update_columns: bool, // generate update_columns, needs derive Columns
+	pub(crate) quote_all: bool,      // double-quote all identifiers
+	pub(crate) log_audit: bool,      // log table columns log_id, log_op, log_at
+	pub(crate) log_insert: bool,     // log table INSERT trigger
+	pub(crate) log_changed: bool,    // log table UPDATE trigger only if a column changed
+	pub(crate) log_sparse: bool,     // log table NULL for unchanged columns, log_mask column
+	pub(crate) restore: bool,        // generate restore_by from the log table, needs derive Update
+	pub(crate) index: Vec<TabIndex>, // indexes of the struct attribute index(...)
+	restore_span: Option<Span>,
+}
+
+impl TabAttr {
+	/// Add the items of one `#[sql(...)]` attribute.
+	fn push(
+		&mut self,
+		attributes: Vec<ParsedAttribute>,
+		lists: Vec<(Ident, Vec<ParsedAttribute>)>,
+	) -> Result {
 		for attr in attributes {
 			match attr {
-				ParsedAttribute::Tag(key) => {
-					return Err(Error::custom_at("unknown table attr", key.span()));
-				}
+				ParsedAttribute::Tag(key) => match key.to_string().as_str() {
+					"update_columns" => self.update_columns = true,
+					"quote_all" => self.quote_all = true,
+					"log_audit" => self.log_audit = true,
+					"log_insert" => self.log_insert = true,
+					"log_changed" => self.log_changed = true,
+					"log_sparse" => {
+						self.log_changed = true;
+						self.log_sparse = true;
+					}
+					"restore" => {
+						self.restore_span = Some(key.span());
+						self.restore = true;
+					}
+					_ => return Err(Error::custom_at("unknown table attr", key.span())),
+				},
 				ParsedAttribute::Property(key, val) => match key.to_string().as_str() {
-					"constraint" => tab.constraint = literal_str(val)?,
-					"option" => tab.option = literal_str(val)?,
+					"name" => self.name = literal_str(val)?,
+					"rename_all" => {
+						let span = val.span();
+						self.rename_all = literal_str(val)?;
+						if rename_case(&self.rename_all).is_none() {
+							return Err(Error::custom_at(
+								"rename_all should be one of lowercase, UPPERCASE, PascalCase, camelCase, snake_case, SCREAMING_SNAKE_CASE",
+								span,
+							));
+						}
+					}
+					"constraint" => self.constraint = literal_str(val)?,
+					"option" => self.option = literal_str(val)?,
+					"upsert" => self.upsert = literal_str(val)?,
 					_ => return Err(Error::custom_at("unknown table attr", key.span())),
 				},
 				_ => {}
 			}
 		}
-		Ok(Some(tab))
+		for (key, items) in lists {
+			match key.to_string().as_str() {
+				"index" => self.index.push(TabIndex::parse(key, items)?),
+				_ => return Err(Error::custom_at("unknown table attr", key.span())),
+			}
+		}
+		Ok(())
 	}
 }
 
+/// Table attributes of all `#[sql(...)]` attributes of a struct or enum.
+pub(crate) fn tab_attr(attributes: &[Attribute]) -> Result<TabAttr> {
+	let mut tab = TabAttr::default();
+	for attribute in attributes {
+		if let Some((items, lists)) = parse_tab_attribute(&attribute.tokens)? {
+			tab.push(items, lists)?;
+		}
+	}
+	if let Some(span) = tab.restore_span {
+		if !tab.log_audit {
+			return Err(Error::custom_at("restore needs log_audit", span));
+		}
+	}
+	Ok(tab)
+}
+
+/// Items of `#[sql(...)]`, the lists `key(...)` apart from the tags and properties.
+///
+/// virtue parses only `key` and `key = "val"`, so the lists are cut out before
+/// and each list is parsed like an attribute of its own.
+#[allow(clippy::type_complexity)]
+fn parse_tab_attribute(
+	group: &Group,
+) -> Result<Option<(Vec<ParsedAttribute>, Vec<(Ident, Vec<ParsedAttribute>)>)>> {
+	let mut stream = group.stream().into_iter();
+	let (Some(TokenTree::Ident(prefix)), Some(TokenTree::Group(args))) =
+		(stream.next(), stream.next())
+	else {
+		return Ok(None);
+	};
+	if prefix.to_string() != "sql" {
+		return Ok(None);
+	}
+	let mut plain = Vec::new();
+	let mut lists = Vec::new();
+	let mut tokens = args.stream().into_iter().peekable();
+	while let Some(token) = tokens.next() {
+		match (token, tokens.peek()) {
+			(TokenTree::Ident(key), Some(TokenTree::Group(list)))
+				if list.delimiter() == Delimiter::Parenthesis =>
+			{
+				let tagged = Group::new(
+					Delimiter::Bracket,
+					[
+						TokenTree::Ident(key.clone()),
+						TokenTree::Group(list.clone()),
+					]
+					.into_iter()
+					.collect(),
+				);
+				tokens.next();
+				if matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ',') {
+					tokens.next();
+				}
+				let items = parse_tagged_attribute(&tagged, &key.to_string())?.unwrap_or_default();
+				lists.push((key, items));
+			}
+			(token, _) => plain.push(token),
+		}
+	}
+	let plain = Group::new(
+		Delimiter::Bracket,
+		[
+			TokenTree::Ident(prefix),
+			TokenTree::Group(Group::new(args.delimiter(), plain.into_iter().collect())),
+		]
+		.into_iter()
+		.collect(),
+	);
+	Ok(parse_tagged_attribute(&plain, "sql")?.map(|items| (items, lists)))
+}
+
+/// Struct attribute `index(cols = "a,lower(b)", unique, where = "expr", name = "index-name")`
 #[derive(Debug, Default)]
+pub(crate) struct TabIndex {
+	pub(crate) name: String,   // index-name, default {tab_name}_{cols}_idx
+	pub(crate) cols: String,   // indexed columns and expressions
+	pub(crate) unique: bool,   // UNIQUE index
+	pub(crate) filter: String, // partial index WHERE expr
+}
+
+impl TabIndex {
+	fn parse(key: Ident, items: Vec<ParsedAttribute>) -> Result<Self> {
+		let mut index = Self::default();
+		for item in items {
+			match item {
+				ParsedAttribute::Tag(key) => match key.to_string().as_str() {
+					"unique" => index.unique = true,
+					_ => return Err(Error::custom_at("unknown index attr", key.span())),
+				},
+				ParsedAttribute::Property(key, val) => match key.to_string().as_str() {
+					"name" => index.name = literal_str(val)?,
+					"cols" => index.cols = literal_str(val)?,
+					"where" => index.filter = literal_str(val)?,
+					_ => return Err(Error::custom_at("unknown index attr", key.span())),
+				},
+				_ => {}
+			}
+		}
+		if index.cols.trim().is_empty() {
+			return Err(Error::custom_at("index needs cols", key.span()));
+		}
+		Ok(index)
+	}
+}
+
+#[derive(Debug, Default, PartialEq)]
 pub(crate) struct ColAttr {
-	pub(crate) typ: String,        // type-name
-	pub(crate) constraint: String, // column-constraint
+	pub(crate) name: String,           // column-name
+	pub(crate) typ: String,            // type-name
+	pub(crate) constraint: String,     // column-constraint
+	pub(crate) version: bool,          // optimistic locking version column
+	pub(crate) pk: bool,               // PRIMARY KEY
+	pub(crate) unique: bool,           // UNIQUE
+	pub(crate) references: String,     // REFERENCES foreign-table(column)
+	pub(crate) on_delete: String,      // ON DELETE action of references
+	pub(crate) default: String,        // DEFAULT value
+	pub(crate) check: String,          // CHECK (expr)
+	pub(crate) skip_get: bool,         // no get_by fn for PRIMARY KEY / UNIQUE
+	pub(crate) skip: bool,             // no column, Default when reading rows
+	pub(crate) readonly: bool,         // column filled by the db, not bound in INSERT / UPDATE
+	pub(crate) generated: String,      // GENERATED ALWAYS AS (expr)
+	pub(crate) stored: bool,           // STORED generated column, else VIRTUAL
+	pub(crate) flatten: bool,          // columns of a nested struct with derive FlattenSql
+	pub(crate) prefix: Option<String>, // column-name prefix of a flattened field
+	pub(crate) unchecked: bool,        // no range CHECK of the rust type
+	pub(crate) index: Option<String>,  // index-name, empty for {tab_name}_{col_name}_idx
+}
+
+impl ColAttr {
+	/// Attribute pk or constraint "PRIMARY KEY..." in all caps.
+	pub(crate) fn is_pk(&self) -> bool {
+		self.pk || self.constraint.starts_with("PRIMARY KEY")
+	}
+
+	/// Attribute unique or constraint "UNIQUE..." in all caps.
+	pub(crate) fn is_unique(&self) -> bool {
+		self.unique || self.constraint.starts_with("UNIQUE")
+	}
+
+	/// Attribute references or constraint "REFERENCES ..." in all caps.
+	pub(crate) fn is_fk(&self) -> bool {
+		!self.references.is_empty() || self.constraint.starts_with("REFERENCES ")
+	}
+
+	/// Attribute readonly or generated or constraint "GENERATED ..." in all caps, not bound in INSERT / UPDATE.
+	pub(crate) fn is_readonly(&self) -> bool {
+		self.readonly || !self.generated.is_empty() || self.constraint.starts_with("GENERATED ")
+	}
+
+	/// Column constraints rendered from the attributes, followed by the constraint str.
+	pub(crate) fn sql_constraint(&self) -> String {
+		let mut s = Vec::new();
+		if self.pk {
+			s.push("PRIMARY KEY".to_string());
+		}
+		if self.unique {
+			s.push("UNIQUE".to_string());
+		}
+		if !self.check.is_empty() {
+			s.push(format!("CHECK ({})", self.check));
+		}
+		if !self.default.is_empty() {
+			s.push(format!("DEFAULT {}", self.default));
+		}
+		if !self.generated.is_empty() {
+			let storage = if self.stored { "STORED" } else { "VIRTUAL" };
+			s.push(format!(
+				"GENERATED ALWAYS AS ({}) {storage}",
+				self.generated
+			));
+		}
+		if !self.references.is_empty() {
+			s.push(format!("REFERENCES {}", self.references));
+			if !self.on_delete.is_empty() {
+				s.push(format!("ON DELETE {}", self.on_delete));
+			}
+		}
+		if !self.constraint.is_empty() {
+			s.push(self.constraint.clone());
+		}
+		s.join(" ")
+	}
 }
 
 impl FromAttribute for ColAttr {
@@ -75,19 +1704,105 @@ impl FromAttribute for ColAttr {
 			return Ok(None);
 		};
 		let mut col = Self::default();
+		let mut on_delete_span = None;
+		let mut skip_span = None;
+		let mut generated_span = None;
+		let mut stored_span = None;
+		let mut flatten_span = None;
+		let mut prefix_span = None;
 		for attr in attributes {
 			match attr {
-				ParsedAttribute::Tag(i) => {
-					return Err(Error::custom_at("unknown column attr", i.span()));
-				}
+				ParsedAttribute::Tag(key) => match key.to_string().as_str() {
+					"version" => col.version = true,
+					"pk" => col.pk = true,
+					"unique" => col.unique = true,
+					"skip_get" => col.skip_get = true,
+					"skip" => {
+						skip_span = Some(key.span());
+						col.skip = true;
+					}
+					"readonly" => col.readonly = true,
+					"unchecked" => col.unchecked = true,
+					"index" => col.index = Some(String::new()),
+					"flatten" => {
+						flatten_span = Some(key.span());
+						col.flatten = true;
+					}
+					"stored" => {
+						stored_span = Some(key.span());
+						col.stored = true;
+					}
+					_ => return Err(Error::custom_at("unknown column attr", key.span())),
+				},
 				ParsedAttribute::Property(key, val) => match key.to_string().as_str() {
+					"name" => col.name = literal_str(val)?,
 					"typ" => col.typ = literal_str(val)?,
 					"constraint" => col.constraint = literal_str(val)?,
+					"references" => col.references = literal_str(val)?,
+					"on_delete" => {
+						on_delete_span = Some(key.span());
+						col.on_delete = literal_str(val)?;
+					}
+					"default" => col.default = literal_str(val)?,
+					"check" => col.check = literal_str(val)?,
+					"index" => col.index = Some(literal_str(val)?),
+					"prefix" => {
+						prefix_span = Some(key.span());
+						col.prefix = Some(literal_str(val)?);
+					}
+					"generated" => {
+						generated_span = Some(key.span());
+						col.generated = literal_str(val)?;
+					}
 					_ => return Err(Error::custom_at("unknown column attr", key.span())),
 				},
 				_ => {}
 			}
 		}
+		if let Some(span) = on_delete_span {
+			if col.references.is_empty() {
+				return Err(Error::custom_at("on_delete needs references", span));
+			}
+		}
+		if let Some(span) = prefix_span {
+			if !col.flatten {
+				return Err(Error::custom_at("prefix needs flatten", span));
+			}
+		}
+		if let Some(span) = flatten_span {
+			let plain = ColAttr {
+				flatten: true,
+				prefix: col.prefix.clone(),
+				..Default::default()
+			};
+			if col != plain {
+				return Err(Error::custom_at(
+					"flatten can only be combined with prefix",
+					span,
+				));
+			}
+		}
+		if let Some(span) = stored_span {
+			if col.generated.is_empty() {
+				return Err(Error::custom_at("stored needs generated", span));
+			}
+		}
+		if let Some(span) = generated_span {
+			if col.pk || col.version || !col.default.is_empty() {
+				return Err(Error::custom_at(
+					"generated can not be combined with pk, version or default",
+					span,
+				));
+			}
+		}
+		if let Some(span) = skip_span {
+			if col.pk || col.unique || col.version || col.is_readonly() {
+				return Err(Error::custom_at(
+					"skip can not be combined with pk, unique, version or readonly",
+					span,
+				));
+			}
+		}
 		Ok(Some(col))
 	}
 }
@@ -184,4 +1899,110 @@ mod tests {
 		// Acronym
 		t("HTTPRequest", "http_request");
 	}
+
+	#[test]
+	fn split_list() {
+		assert_eq!(
+			super::split_list("a, lower(b),substr(c,1,2) DESC,"),
+			["a", "lower(b)", "substr(c,1,2) DESC"]
+		);
+		assert_eq!(super::name_part("substr(c,1,2) DESC"), "substr_c_1_2_DESC");
+		assert_eq!(super::name_part("\\\"b\\\""), "b");
+	}
+
+	#[test]
+	fn col_typ() {
+		fn t(rust_typ: &str, typ: &str) {
+			assert_eq!(typ, super::col_typ(rust_typ))
+		}
+		t("i64", "INTEGER NOT NULL");
+		t("std::primitive::i64", "INTEGER NOT NULL");
+		t("::core::primitive::u8", "INTEGER NOT NULL");
+		t("Option<&str>", "TEXT");
+		t("Option<&'a str>", "TEXT");
+		t("std::option::Option<std::string::String>", "TEXT");
+		t("Box<str>", "TEXT NOT NULL");
+		t("Cow<'static, str>", "TEXT NOT NULL");
+		t("Option<std::sync::Arc<str>>", "TEXT");
+		t("&'a [u8]", "BLOB NOT NULL");
+		t("Option<Box<[u8]>>", "BLOB");
+		t("Option<f64>", "REAL");
+		t("u64", "ANY");
+		t("MyNewtype", "ANY");
+		t("Option<MyNewtype>", "ANY");
+	}
+
+	#[test]
+	fn sql_type_path() {
+		fn t(rust_typ: &str, typ: &str, nullable: bool) {
+			assert_eq!((typ.to_string(), nullable), super::sql_type_path(rust_typ))
+		}
+		t("MyNewtype", "MyNewtype", false);
+		t("Option<my::MyNewtype>", "my::MyNewtype", true);
+		t("Cow<'a, MyNewtype>", "Cow<'_, MyNewtype>", false);
+		t("Option<&'static MyNewtype>", "&'static MyNewtype", true);
+	}
+
+	#[test]
+	#[cfg(all(
+		feature = "chrono",
+		feature = "rust_decimal",
+		feature = "serde_json",
+		feature = "time",
+		feature = "uuid"
+	))]
+	fn col_typ_crates() {
+		fn t(rust_typ: &str, typ: &str) {
+			assert_eq!(typ, super::col_typ(rust_typ))
+		}
+		t("uuid::Uuid", "BLOB NOT NULL");
+		t("Option<Uuid>", "BLOB");
+		t("chrono::NaiveDate", "TEXT NOT NULL");
+		t("DateTime<Utc>", "TEXT NOT NULL");
+		t("Option<time::OffsetDateTime>", "TEXT");
+		t("::time::Date", "TEXT NOT NULL");
+		t("Date", "ANY");
+		t("std::option::Option<serde_json::Value>", "TEXT");
+		t("Value", "ANY");
+		t("sqlx::types::Json<Vec<i64>>", "TEXT NOT NULL");
+		t("rust_decimal::Decimal", "TEXT NOT NULL");
+		t("Option<Decimal>", "TEXT");
+		assert!(super::is_json("Option<JsonValue>"));
+	}
+
+	#[test]
+	fn quote() {
+		fn t(name: &str, quote_all: bool, q: &str) {
+			assert_eq!(q, super::quote(name, quote_all))
+		}
+		t("name", false, "name");
+		t("name", true, "\\\"name\\\"");
+		t("order", false, "\\\"order\\\"");
+		t("Group", false, "\\\"Group\\\"");
+		t("full name", false, "\\\"full name\\\"");
+		t("2nd", false, "\\\"2nd\\\"");
+		t("a\"b", false, "\\\"a\\\"\\\"b\\\"");
+	}
+
+	#[test]
+	fn tab_key_cols() {
+		fn t(constraint: &str, key: &str, cols: &[&[&str]]) {
+			assert_eq!(cols, super::tab_key_cols(constraint, key))
+		}
+		t("", "PRIMARY KEY", &[]);
+		t("PRIMARY KEY(a,b)", "PRIMARY KEY", &[&["a", "b"]]);
+		t("PRIMARY KEY (a, b DESC)", "PRIMARY KEY", &[&["a", "b"]]);
+		t("primary key(a,b)", "PRIMARY KEY", &[]);
+		t(
+			"PRIMARY KEY(\"order\",[group])",
+			"PRIMARY KEY",
+			&[&["order", "group"]],
+		);
+		t(
+			"UNIQUE(a,b), CHECK(a > b), UNIQUE(c COLLATE NOCASE)",
+			"UNIQUE",
+			&[&["a", "b"], &["c"]],
+		);
+		t("FOREIGN KEY(a) REFERENCES x(id)", "PRIMARY KEY", &[]);
+	}
 }
@@ -3,8 +3,9 @@
 #![allow(unused)]
 
 use wb_sqlite::{
-	CreateIndexSql, CreateTableLogSql, CreateTableSql, Get, Insert, InsertSync, SelectAsSql,
-	SelectSql, Update, UpdateSync,
+	Columns, CreateIndexSql, CreateTableLogSql, CreateTableSql, Delete, DeleteSync, Flatten,
+	FlattenSql, FlattenSync, FromRowSync, Get, GetSync, History, HistorySync, Insert, InsertSync,
+	SelectAsSql, SelectSql, SqlType, TextEnum, TextEnumSync, Update, UpdateSync,
 };
 
 #[derive(Debug, Default, CreateTableSql, SelectSql, Insert, InsertSync)]
@@ -29,7 +30,9 @@ struct SinglePk {
 	sqlx::FromRow,
 	Update,
 	UpdateSync,
+	Columns,
 )]
+#[sql(update_columns)]
 struct Double {
 	#[sql(constraint = "PRIMARY KEY")]
 	id: i64,
@@ -44,10 +47,14 @@ struct Double {
 	CreateTableLogSql,
 	SelectSql,
 	Get,
+	GetSync,
 	Insert,
 	InsertSync,
 	Update,
 	UpdateSync,
+	Delete,
+	DeleteSync,
+	FromRowSync,
 	sqlx::FromRow,
 )]
 struct Record {
@@ -57,9 +64,8 @@ struct Record {
 	pub fk: i64,
 	#[sql(constraint = "UNIQUE")]
 	name: String,
-	#[sql(constraint = "CHECK (ok IN (0,1))")]
+	#[sql(unchecked, constraint = "CHECK (ok IN (0,1))")]
 	ok: bool,
-	#[sql(constraint = "CHECK (pos >= 0)")]
 	pos: u32,
 	num: i64,
 	sci_val: f64,
@@ -86,6 +92,7 @@ struct Record {
 	InsertSync,
 	Update,
 	UpdateSync,
+	FromRowSync,
 	sqlx::FromRow,
 )]
 #[sqlas(from = "record where id < 3 order by id desc")]
@@ -110,6 +117,429 @@ struct NtoMrel {
 	m: i64,
 }
 
+#[derive(
+	Debug,
+	Default,
+	CreateTableSql,
+	Get,
+	GetSync,
+	Insert,
+	InsertSync,
+	Update,
+	UpdateSync,
+	sqlx::FromRow,
+)]
+struct TextPk {
+	#[sql(constraint = "PRIMARY KEY")]
+	code: String,
+	name: String,
+}
+
+#[derive(Debug, Default, PartialEq, CreateTableSql, TextEnum, TextEnumSync)]
+enum Status {
+	#[default]
+	Active,
+	Inactive,
+}
+
+#[derive(CreateTableSql, SqlType)]
+#[sql(name = "prio")]
+enum Priority {
+	Low,
+	High,
+}
+
+#[derive(Debug, CreateTableSql, Get, GetSync, Insert, InsertSync, sqlx::FromRow)]
+struct Task {
+	#[sql(constraint = "PRIMARY KEY")]
+	id: i64,
+	#[sql(typ = "TEXT", constraint = "REFERENCES status(name)")]
+	status: Status,
+}
+
+#[derive(
+	Debug,
+	Default,
+	CreateTableSql,
+	Get,
+	GetSync,
+	Insert,
+	InsertSync,
+	Update,
+	UpdateSync,
+	sqlx::FromRow,
+)]
+struct Account {
+	#[sql(constraint = "PRIMARY KEY")]
+	id: i64,
+	balance: i64,
+	#[sql(version)]
+	version: i64,
+}
+
+#[derive(
+	Debug,
+	Default,
+	CreateTableSql,
+	Delete,
+	DeleteSync,
+	Get,
+	GetSync,
+	Insert,
+	InsertSync,
+	Update,
+	UpdateSync,
+	sqlx::FromRow,
+)]
+#[sql(constraint = "PRIMARY KEY(person,club), UNIQUE (club, seat)")]
+struct Member {
+	person: i64,
+	club: i64,
+	seat: i64,
+	role: String,
+}
+
+#[derive(
+	Debug, Default, CreateTableSql, CreateIndexSql, Get, GetSync, Insert, InsertSync, sqlx::FromRow,
+)]
+struct Pet {
+	#[sql(pk)]
+	id: i64,
+	#[sql(unique, check = "length(name) > 0")]
+	name: String,
+	#[sql(unique, skip_get)]
+	chip: String,
+	#[sql(references = "single_pk(id)", on_delete = "CASCADE")]
+	owner: i64,
+	#[sql(default = "'dog'", constraint = "COLLATE NOCASE")]
+	kind: String,
+}
+
+#[derive(
+	Debug,
+	Default,
+	Columns,
+	CreateIndexSql,
+	CreateTableLogSql,
+	CreateTableSql,
+	FromRowSync,
+	Get,
+	GetSync,
+	Insert,
+	InsertSync,
+	SelectSql,
+	Update,
+	UpdateSync,
+	sqlx::FromRow,
+)]
+#[sql(name = "tblCustomer", rename_all = "PascalCase", update_columns)]
+struct Customer {
+	#[sql(pk, name = "CustomerID")]
+	id: i64,
+	#[sql(unique)]
+	full_name: String,
+	#[sql(references = "single_pk(id)")]
+	group_id: i64,
+}
+
+/// second struct for the same table
+#[derive(Debug, GetSync, UpdateSync)]
+#[sql(name = "tblCustomer")]
+struct CustomerName {
+	#[sql(pk, name = "CustomerID")]
+	id: i64,
+	#[sql(name = "FullName")]
+	name: String,
+}
+
+/// SQLite keywords as table and column names, raw identifiers
+#[derive(
+	Debug,
+	Default,
+	Columns,
+	CreateIndexSql,
+	CreateTableLogSql,
+	CreateTableSql,
+	Delete,
+	DeleteSync,
+	FromRowSync,
+	Get,
+	GetSync,
+	Insert,
+	InsertSync,
+	SelectSql,
+	Update,
+	UpdateSync,
+	sqlx::FromRow,
+)]
+#[sql(update_columns, upsert = "group")]
+struct Order {
+	#[sql(pk)]
+	id: i64,
+	#[sql(unique)]
+	group: String,
+	r#type: String,
+	#[sql(references = "single_pk(id)")]
+	r#where: i64,
+}
+
+#[derive(Debug, CreateTableSql, SelectSql)]
+#[sql(quote_all, rename_all = "PascalCase")]
+struct QuoteAll {
+	id: i64,
+	full_name: String,
+}
+
+/// skip, readonly + generated fields
+#[derive(
+	Debug,
+	Default,
+	Columns,
+	CreateTableSql,
+	FromRowSync,
+	Get,
+	GetSync,
+	Insert,
+	InsertSync,
+	SelectAsSql,
+	SelectSql,
+	Update,
+	UpdateSync,
+	sqlx::FromRow,
+)]
+#[sql(update_columns)]
+struct Note {
+	#[sql(pk)]
+	id: i64,
+	text: String,
+	#[sql(readonly, default = "CURRENT_TIMESTAMP")]
+	created: String,
+	#[sql(generated = "length(text)", stored)]
+	len: i64,
+	#[sql(generated = "upper(text)")]
+	upper: String,
+	#[sql(skip)]
+	#[sqlx(skip)]
+	cache: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Flatten, FlattenSql, FlattenSync)]
+struct Address {
+	#[sql(default = "''")]
+	street: String,
+	#[sql(name = "town")]
+	city: String,
+	zip: Option<u16>,
+}
+
+#[allow(dead_code)]
+#[derive(FlattenSql)]
+struct Meta {
+	data: serde_json::Value,
+	#[sql(unchecked)]
+	level: u8,
+	hidden: bool,
+	cents: Cents,
+}
+
+#[derive(SqlType)]
+struct Cents(i64);
+
+#[derive(SqlType)]
+struct Refund(Cents);
+
+#[derive(SqlType)]
+struct Code {
+	#[sql(typ = "BLOB")]
+	_raw: [u8; 4],
+}
+
+#[allow(dead_code)]
+#[derive(CreateTableSql, CreateTableLogSql, FlattenSql)]
+struct Gen<T> {
+	id: i64,
+	x: Vec<T>,
+	cents: Cents,
+}
+
+#[allow(dead_code)]
+#[derive(CreateTableSql, CreateTableLogSql)]
+#[sql(log_audit, log_sparse)]
+struct Ledger {
+	#[sql(pk)]
+	id: i64,
+	cents: Cents,
+	refund: Option<Refund>,
+	code: Code,
+	prio: Priority,
+	other: std::time::Duration,
+}
+
+#[derive(
+	Debug,
+	Default,
+	Columns,
+	CreateTableLogSql,
+	CreateTableSql,
+	FromRowSync,
+	Get,
+	GetSync,
+	Insert,
+	InsertSync,
+	SelectSql,
+	Update,
+	UpdateSync,
+)]
+#[sql(update_columns, upsert = "name")]
+struct Shop {
+	#[sql(pk)]
+	id: i64,
+	#[sql(unique)]
+	name: String,
+	#[sql(flatten)]
+	address: Address,
+	#[sql(flatten, prefix = "bill_")]
+	billing: Address,
+	#[sql(version)]
+	ver: i64,
+}
+
+impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Shop {
+	fn from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+		use sqlx::Row;
+		Ok(Self {
+			id: row.try_get("id")?,
+			name: row.try_get("name")?,
+			address: Address::flatten_from_row(row, "address_")?,
+			billing: Address::flatten_from_row(row, "bill_")?,
+			ver: row.try_get("ver")?,
+		})
+	}
+}
+
+/// Decimal is bound as its string, sqlx and rusqlite have no SQLite support for it
+#[allow(dead_code)]
+#[derive(CreateTableSql)]
+struct Invoice {
+	#[sql(pk)]
+	id: i64,
+	total: rust_decimal::Decimal,
+	discount: Option<rust_decimal::Decimal>,
+}
+
+#[derive(Debug, PartialEq, CreateTableSql, Get, GetSync, Insert, InsertSync, sqlx::FromRow)]
+struct Event {
+	#[sql(pk)]
+	id: i64,
+	uuid: uuid::Uuid,
+	day: chrono::NaiveDate,
+	at: chrono::DateTime<chrono::Utc>,
+	start: time::OffsetDateTime,
+	end: Option<time::Date>,
+	data: serde_json::Value,
+}
+
+#[derive(Debug, CreateTableSql, CreateTableLogSql, InsertSync, UpdateSync, DeleteSync)]
+#[sql(log_audit, log_insert)]
+struct Stock {
+	#[sql(pk)]
+	id: i64,
+	item: String,
+	qty: i32,
+}
+
+#[derive(CreateTableSql, CreateTableLogSql, InsertSync, UpdateSync)]
+#[sql(constraint = "PRIMARY KEY(rack,slot)", log_sparse)]
+struct Shelf {
+	rack: i64,
+	slot: i64,
+	qty: i32,
+}
+
+/// no upsert, RETURNING rowid fails without a rowid
+#[derive(CreateTableSql, Insert, InsertSync)]
+#[sql(option = "WITHOUT ROWID")]
+struct Label {
+	#[sql(pk)]
+	name: String,
+	color: String,
+}
+
+#[derive(CreateTableSql, CreateIndexSql)]
+#[sql(index(cols = "tenant, lower(email)", unique, where = "deleted_at IS NULL"))]
+#[sql(index(cols = "created_at DESC", name = "login_recent"))]
+struct Login {
+	#[sql(pk)]
+	id: i64,
+	#[sql(index = "login_tenant_nick")]
+	tenant: i64,
+	#[sql(index = "login_tenant_nick")]
+	nick: String,
+	email: String,
+	#[sql(index)]
+	created_at: f64,
+	deleted_at: Option<f64>,
+	#[sql(references = "login(id)", index)]
+	invited_by: Option<i64>,
+}
+
+#[derive(Debug, CreateTableSql, CreateTableLogSql, InsertSync, UpdateSync)]
+#[sql(log_changed)]
+struct Depot {
+	#[sql(pk)]
+	id: i64,
+	#[sql(flatten)]
+	address: Address,
+	#[sql(version)]
+	ver: i64,
+}
+
+#[derive(Debug, CreateTableSql, CreateTableLogSql, InsertSync, UpdateSync, DeleteSync)]
+#[sql(log_audit, log_sparse)]
+struct Bin {
+	#[sql(pk)]
+	id: i64,
+	item: String,
+	qty: i32,
+}
+
+#[derive(
+	Debug,
+	CreateTableSql,
+	CreateTableLogSql,
+	History,
+	HistorySync,
+	Insert,
+	InsertSync,
+	Update,
+	UpdateSync,
+	sqlx::FromRow,
+)]
+#[sql(log_audit, log_insert, restore)]
+struct Price {
+	#[sql(pk)]
+	id: i64,
+	amount: i64,
+	#[sql(version)]
+	ver: i64,
+}
+
+#[derive(CreateTableSql, CreateTableLogSql, HistorySync, InsertSync, UpdateSync)]
+#[sql(log_audit, restore)]
+struct Fee {
+	#[sql(pk)]
+	id: i64,
+	amount: i64,
+}
+
+/// unix time between two log entries, log_at has millisecond precision
+fn log_pause(now: impl FnOnce() -> f64) -> f64 {
+	std::thread::sleep(std::time::Duration::from_millis(5));
+	let at = now();
+	std::thread::sleep(std::time::Duration::from_millis(5));
+	at
+}
+
 #[test]
 fn create() -> Result<(), rusqlite::Error> {
 	fn eq(l: &str, r: &str) {
@@ -128,17 +558,32 @@ fn create() -> Result<(), rusqlite::Error> {
 	eq(SinglePk::SELECT_SQL, "SELECT id FROM single_pk");
 	eq(
 		Double::CREATE_TABLE_SQL,
-		"CREATE TABLE double (id INTEGER NOT NULL PRIMARY KEY, num INTEGER NOT NULL) STRICT;",
+		"CREATE TABLE double (id INTEGER NOT NULL PRIMARY KEY, num INTEGER NOT NULL CHECK (num BETWEEN 0 AND 4294967295)) STRICT;",
 	);
 	eq(Double::SELECT_SQL, "SELECT id,num FROM double");
 	eq(
 		Record::CREATE_TABLE_SQL,
-		"CREATE TABLE record (id INTEGER NOT NULL PRIMARY KEY, fk INTEGER NOT NULL REFERENCES single_pk(id), name TEXT NOT NULL UNIQUE, ok INTEGER NOT NULL CHECK (ok IN (0,1)), pos INTEGER NOT NULL CHECK (pos >= 0), num INTEGER NOT NULL, sci_val REAL NOT NULL, note TEXT NOT NULL, data BLOB NOT NULL, opt_ok INTEGER, opt_pos INTEGER, opt_num INTEGER, opt_sci_val REAL, opt_note TEXT, opt_data BLOB, any_data ANY) STRICT;",
+		"CREATE TABLE record (id INTEGER NOT NULL PRIMARY KEY, fk INTEGER NOT NULL REFERENCES single_pk(id), name TEXT NOT NULL UNIQUE, ok INTEGER NOT NULL CHECK (ok IN (0,1)), pos INTEGER NOT NULL CHECK (pos BETWEEN 0 AND 4294967295), num INTEGER NOT NULL, sci_val REAL NOT NULL, note TEXT NOT NULL, data BLOB NOT NULL, opt_ok INTEGER CHECK (opt_ok IN (0,1)), opt_pos INTEGER CHECK (opt_pos BETWEEN 0 AND 4294967295), opt_num INTEGER, opt_sci_val REAL, opt_note TEXT, opt_data BLOB, any_data ANY) STRICT;",
 	);
 	eq(
 		Record::CREATE_INDEX_SQL,
 		"CREATE INDEX record_fk_idx ON record(fk); ",
 	);
+	eq(Record::DROP_INDEX_SQL, "DROP INDEX record_fk_idx; ");
+	eq(
+		Login::CREATE_INDEX_SQL,
+		"CREATE INDEX login_tenant_nick ON login(tenant,nick); CREATE INDEX login_created_at_idx ON login(created_at); CREATE INDEX login_invited_by_idx ON login(invited_by); CREATE UNIQUE INDEX login_tenant_lower_email_idx ON login(tenant,lower(email)) WHERE deleted_at IS NULL; CREATE INDEX login_recent ON login(created_at DESC); ",
+	);
+	eq(
+		Login::DROP_INDEX_SQL,
+		"DROP INDEX login_tenant_nick; DROP INDEX login_created_at_idx; DROP INDEX login_invited_by_idx; DROP INDEX login_tenant_lower_email_idx; DROP INDEX login_recent; ",
+	);
+	eq(Record::DROP_TABLE_SQL, "DROP TABLE record;");
+	eq(Status::DROP_TABLE_SQL, "DROP TABLE status;");
+	eq(
+		Stock::DROP_TABLE_LOG_SQL,
+		"DROP TRIGGER stock_insert; DROP TRIGGER stock_update; DROP TRIGGER stock_delete; DROP INDEX stock_log_id_idx; DROP TABLE stock_log;",
+	);
 	eq(
 		Record::CREATE_TABLE_LOG_SQL,
 		"CREATE TABLE record_log (id INTEGER NOT NULL, fk INTEGER NOT NULL, name TEXT NOT NULL, ok INTEGER NOT NULL, pos INTEGER NOT NULL, num INTEGER NOT NULL, sci_val REAL NOT NULL, note TEXT NOT NULL, data BLOB NOT NULL, opt_ok INTEGER, opt_pos INTEGER, opt_num INTEGER, opt_sci_val REAL, opt_note TEXT, opt_data BLOB, any_data ANY) STRICT; CREATE INDEX record_log_id_idx ON record_log(id); CREATE TRIGGER record_update UPDATE ON record BEGIN INSERT INTO record_log (id,fk,name,ok,pos,num,sci_val,note,data,opt_ok,opt_pos,opt_num,opt_sci_val,opt_note,opt_data,any_data) VALUES (OLD.id,OLD.fk,OLD.name,OLD.ok,OLD.pos,OLD.num,OLD.sci_val,OLD.note,OLD.data,OLD.opt_ok,OLD.opt_pos,OLD.opt_num,OLD.opt_sci_val,OLD.opt_note,OLD.opt_data,OLD.any_data); END; CREATE TRIGGER record_delete DELETE ON record BEGIN INSERT INTO record_log (id,fk,name,ok,pos,num,sci_val,note,data,opt_ok,opt_pos,opt_num,opt_sci_val,opt_note,opt_data,any_data) VALUES (OLD.id,OLD.fk,OLD.name,OLD.ok,OLD.pos,OLD.num,OLD.sci_val,OLD.note,OLD.data,OLD.opt_ok,OLD.opt_pos,OLD.opt_num,OLD.opt_sci_val,OLD.opt_note,OLD.opt_data,OLD.any_data); END;",
@@ -168,6 +613,146 @@ fn create() -> Result<(), rusqlite::Error> {
 		"CREATE INDEX nto_mrel_n_idx ON nto_mrel(n); CREATE INDEX nto_mrel_m_idx ON nto_mrel(m); ",
 	);
 	eq(NtoMrel::SELECT_SQL, "SELECT n,m FROM nto_mrel");
+	eq(
+		Status::CREATE_TABLE_SQL,
+		"CREATE TABLE status (name TEXT NOT NULL PRIMARY KEY) STRICT; INSERT INTO status (name) VALUES ('Active'),('Inactive');",
+	);
+	eq(
+		Priority::CREATE_TABLE_SQL,
+		"CREATE TABLE prio (name TEXT NOT NULL PRIMARY KEY) STRICT; INSERT INTO prio (name) VALUES ('Low'),('High');",
+	);
+	eq(
+		Task::CREATE_TABLE_SQL,
+		"CREATE TABLE task (id INTEGER NOT NULL PRIMARY KEY, status TEXT REFERENCES status(name)) STRICT;",
+	);
+	eq(
+		Pet::CREATE_TABLE_SQL,
+		"CREATE TABLE pet (id INTEGER NOT NULL PRIMARY KEY, name TEXT NOT NULL UNIQUE CHECK (length(name) > 0), chip TEXT NOT NULL UNIQUE, owner INTEGER NOT NULL REFERENCES single_pk(id) ON DELETE CASCADE, kind TEXT NOT NULL DEFAULT 'dog' COLLATE NOCASE) STRICT;",
+	);
+	eq(
+		Pet::CREATE_INDEX_SQL,
+		"CREATE INDEX pet_owner_idx ON pet(owner); ",
+	);
+	eq(
+		Customer::CREATE_TABLE_SQL,
+		"CREATE TABLE tblCustomer (CustomerID INTEGER NOT NULL PRIMARY KEY, FullName TEXT NOT NULL UNIQUE, GroupId INTEGER NOT NULL REFERENCES single_pk(id)) STRICT;",
+	);
+	eq(
+		Customer::CREATE_INDEX_SQL,
+		"CREATE INDEX tblCustomer_GroupId_idx ON tblCustomer(GroupId); ",
+	);
+	eq(
+		Customer::SELECT_SQL,
+		"SELECT CustomerID AS id,FullName AS full_name,GroupId AS group_id FROM tblCustomer",
+	);
+	eq(CustomerColumn::FullName.name(), "FullName");
+	eq(
+		Account::CREATE_TABLE_SQL,
+		"CREATE TABLE account (id INTEGER NOT NULL PRIMARY KEY, balance INTEGER NOT NULL, version INTEGER NOT NULL DEFAULT 0) STRICT;",
+	);
+
+	eq(
+		Order::CREATE_TABLE_SQL,
+		"CREATE TABLE \"order\" (id INTEGER NOT NULL PRIMARY KEY, \"group\" TEXT NOT NULL UNIQUE, type TEXT NOT NULL, \"where\" INTEGER NOT NULL REFERENCES single_pk(id)) STRICT;",
+	);
+	eq(
+		Order::CREATE_INDEX_SQL,
+		"CREATE INDEX order_where_idx ON \"order\"(\"where\"); ",
+	);
+	eq(
+		Order::CREATE_TABLE_LOG_SQL,
+		"CREATE TABLE order_log (id INTEGER NOT NULL, \"group\" TEXT NOT NULL, type TEXT NOT NULL, \"where\" INTEGER NOT NULL) STRICT; CREATE INDEX order_log_id_idx ON order_log(id); CREATE TRIGGER order_update UPDATE ON \"order\" BEGIN INSERT INTO order_log (id,\"group\",type,\"where\") VALUES (OLD.id,OLD.\"group\",OLD.type,OLD.\"where\"); END; CREATE TRIGGER order_delete DELETE ON \"order\" BEGIN INSERT INTO order_log (id,\"group\",type,\"where\") VALUES (OLD.id,OLD.\"group\",OLD.type,OLD.\"where\"); END;",
+	);
+	eq(
+		Order::SELECT_SQL,
+		"SELECT id,\"group\",type,\"where\" FROM \"order\"",
+	);
+	eq(OrderColumn::Where.name(), "where");
+	eq(
+		QuoteAll::CREATE_TABLE_SQL,
+		"CREATE TABLE \"quote_all\" (\"Id\" INTEGER NOT NULL, \"FullName\" TEXT NOT NULL) STRICT;",
+	);
+	eq(
+		QuoteAll::SELECT_SQL,
+		"SELECT \"Id\" AS \"id\",\"FullName\" AS \"full_name\" FROM \"quote_all\"",
+	);
+
+	eq(
+		Note::CREATE_TABLE_SQL,
+		"CREATE TABLE note (id INTEGER NOT NULL PRIMARY KEY, text TEXT NOT NULL, created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP, len INTEGER NOT NULL GENERATED ALWAYS AS (length(text)) STORED, upper TEXT NOT NULL GENERATED ALWAYS AS (upper(text)) VIRTUAL) STRICT;",
+	);
+	eq(
+		Note::SELECT_SQL,
+		"SELECT id,text,created,len,upper FROM note",
+	);
+	eq(
+		Note::SELECT_AS_SQL,
+		"SELECT id,text,created,len,upper FROM note",
+	);
+	eq(
+		Shop::CREATE_TABLE_SQL,
+		"CREATE TABLE shop (id INTEGER NOT NULL PRIMARY KEY, name TEXT NOT NULL UNIQUE, address_street TEXT NOT NULL DEFAULT '', address_town TEXT NOT NULL, address_zip INTEGER CHECK (address_zip BETWEEN 0 AND 65535), bill_street TEXT NOT NULL DEFAULT '', bill_town TEXT NOT NULL, bill_zip INTEGER CHECK (bill_zip BETWEEN 0 AND 65535), ver INTEGER NOT NULL DEFAULT 0) STRICT;",
+	);
+	assert_eq!(
+		Meta::FLATTEN_COL_CHECKS,
+		["CHECK (json_valid(?))", "", "CHECK (? IN (0,1))", ""]
+	);
+	assert_eq!(
+		Meta::FLATTEN_COL_TYPES,
+		[
+			"TEXT NOT NULL",
+			"INTEGER NOT NULL",
+			"INTEGER NOT NULL",
+			"INTEGER NOT NULL"
+		]
+	);
+	eq(
+		Ledger::CREATE_TABLE_SQL,
+		"CREATE TABLE ledger (id INTEGER NOT NULL PRIMARY KEY, cents INTEGER NOT NULL, refund INTEGER, code BLOB NOT NULL, prio TEXT NOT NULL, other ANY) STRICT;",
+	);
+	assert!(Shelf::CREATE_TABLE_LOG_SQL.starts_with("CREATE TABLE shelf_log (log_mask INTEGER NOT NULL, rack INTEGER NOT NULL, slot INTEGER NOT NULL, qty INTEGER) STRICT; CREATE INDEX shelf_log_rack_slot_idx ON shelf_log(rack,slot); "));
+	// a const block of the generated code can not name T
+	eq(
+		Gen::<u8>::CREATE_TABLE_SQL,
+		"CREATE TABLE gen (id INTEGER NOT NULL, x ANY, cents ANY) STRICT;",
+	);
+	assert!(
+		Gen::<u8>::CREATE_TABLE_LOG_SQL
+			.starts_with("CREATE TABLE gen_log (id INTEGER NOT NULL, x ANY, cents ANY) STRICT;")
+	);
+	assert_eq!(
+		Gen::<u8>::FLATTEN_COL_TYPES,
+		["INTEGER NOT NULL", "ANY", "ANY"]
+	);
+	assert!(Ledger::CREATE_TABLE_LOG_SQL.starts_with("CREATE TABLE ledger_log (log_id INTEGER PRIMARY KEY AUTOINCREMENT, log_op TEXT NOT NULL CHECK (log_op IN ('I','U','D')), log_at REAL NOT NULL DEFAULT (unixepoch('subsec')), log_mask INTEGER NOT NULL, id INTEGER NOT NULL, cents INTEGER, refund INTEGER, code BLOB, prio TEXT, other ANY) STRICT;"));
+	eq(
+		Invoice::CREATE_TABLE_SQL,
+		"CREATE TABLE invoice (id INTEGER NOT NULL PRIMARY KEY, total TEXT NOT NULL, discount TEXT) STRICT;",
+	);
+	eq(
+		Event::CREATE_TABLE_SQL,
+		"CREATE TABLE event (id INTEGER NOT NULL PRIMARY KEY, uuid BLOB NOT NULL, day TEXT NOT NULL, at TEXT NOT NULL, start TEXT NOT NULL, \"end\" TEXT, data TEXT NOT NULL CHECK (json_valid(data))) STRICT;",
+	);
+	eq(
+		Stock::CREATE_TABLE_LOG_SQL,
+		"CREATE TABLE stock_log (log_id INTEGER PRIMARY KEY AUTOINCREMENT, log_op TEXT NOT NULL CHECK (log_op IN ('I','U','D')), log_at REAL NOT NULL DEFAULT (unixepoch('subsec')), id INTEGER NOT NULL, item TEXT NOT NULL, qty INTEGER NOT NULL) STRICT; CREATE INDEX stock_log_id_idx ON stock_log(id); CREATE TRIGGER stock_insert AFTER INSERT ON stock BEGIN INSERT INTO stock_log (log_op,id,item,qty) VALUES ('I',NEW.id,NEW.item,NEW.qty); END; CREATE TRIGGER stock_update UPDATE ON stock BEGIN INSERT INTO stock_log (log_op,id,item,qty) VALUES ('U',OLD.id,OLD.item,OLD.qty); END; CREATE TRIGGER stock_delete DELETE ON stock BEGIN INSERT INTO stock_log (log_op,id,item,qty) VALUES ('D',OLD.id,OLD.item,OLD.qty); END;",
+	);
+	eq(
+		Depot::CREATE_TABLE_LOG_SQL,
+		"CREATE TABLE depot_log (id INTEGER NOT NULL, address_street TEXT NOT NULL, address_town TEXT NOT NULL, address_zip INTEGER, ver INTEGER NOT NULL) STRICT; CREATE INDEX depot_log_id_idx ON depot_log(id); CREATE TRIGGER depot_update UPDATE ON depot WHEN OLD.id IS NOT NEW.id OR OLD.address_street IS NOT NEW.address_street OR OLD.address_town IS NOT NEW.address_town OR OLD.address_zip IS NOT NEW.address_zip BEGIN INSERT INTO depot_log (id,address_street,address_town,address_zip,ver) VALUES (OLD.id,OLD.address_street,OLD.address_town,OLD.address_zip,OLD.ver); END; CREATE TRIGGER depot_delete DELETE ON depot BEGIN INSERT INTO depot_log (id,address_street,address_town,address_zip,ver) VALUES (OLD.id,OLD.address_street,OLD.address_town,OLD.address_zip,OLD.ver); END;",
+	);
+	eq(
+		Bin::CREATE_TABLE_LOG_SQL,
+		"CREATE TABLE bin_log (log_id INTEGER PRIMARY KEY AUTOINCREMENT, log_op TEXT NOT NULL CHECK (log_op IN ('I','U','D')), log_at REAL NOT NULL DEFAULT (unixepoch('subsec')), log_mask INTEGER NOT NULL, id INTEGER NOT NULL, item TEXT, qty INTEGER) STRICT; CREATE INDEX bin_log_id_idx ON bin_log(id); CREATE TRIGGER bin_update UPDATE ON bin WHEN OLD.id IS NOT NEW.id OR OLD.item IS NOT NEW.item OR OLD.qty IS NOT NEW.qty BEGIN INSERT INTO bin_log (log_op,log_mask,id,item,qty) VALUES ('U',(OLD.id IS NOT NEW.id)+(OLD.item IS NOT NEW.item)*2+(OLD.qty IS NOT NEW.qty)*4,OLD.id,CASE WHEN OLD.item IS NOT NEW.item THEN OLD.item END,CASE WHEN OLD.qty IS NOT NEW.qty THEN OLD.qty END); END; CREATE TRIGGER bin_delete DELETE ON bin BEGIN INSERT INTO bin_log (log_op,log_mask,id,item,qty) VALUES ('D',7,OLD.id,OLD.item,OLD.qty); END;",
+	);
+	eq(
+		Shop::SELECT_SQL,
+		"SELECT id,name,address_street,address_town,address_zip,bill_street,bill_town,bill_zip,ver FROM shop",
+	);
+	eq(
+		Shop::CREATE_TABLE_LOG_SQL,
+		"CREATE TABLE shop_log (id INTEGER NOT NULL, name TEXT NOT NULL, address_street TEXT NOT NULL, address_town TEXT NOT NULL, address_zip INTEGER, bill_street TEXT NOT NULL, bill_town TEXT NOT NULL, bill_zip INTEGER, ver INTEGER NOT NULL) STRICT; CREATE INDEX shop_log_id_idx ON shop_log(id); CREATE TRIGGER shop_update UPDATE ON shop BEGIN INSERT INTO shop_log (id,name,address_street,address_town,address_zip,bill_street,bill_town,bill_zip,ver) VALUES (OLD.id,OLD.name,OLD.address_street,OLD.address_town,OLD.address_zip,OLD.bill_street,OLD.bill_town,OLD.bill_zip,OLD.ver); END; CREATE TRIGGER shop_delete DELETE ON shop BEGIN INSERT INTO shop_log (id,name,address_street,address_town,address_zip,bill_street,bill_town,bill_zip,ver) VALUES (OLD.id,OLD.name,OLD.address_street,OLD.address_town,OLD.address_zip,OLD.bill_street,OLD.bill_town,OLD.bill_zip,OLD.ver); END;",
+	);
 
 	let c = rusqlite::Connection::open_in_memory()?;
 	fn x(c: &rusqlite::Connection, sql: &str) {
@@ -185,11 +770,70 @@ fn create() -> Result<(), rusqlite::Error> {
 	x(&c, MapRecord::CREATE_TABLE_SQL);
 	x(&c, NtoMrel::CREATE_TABLE_SQL);
 	x(&c, NtoMrel::CREATE_INDEX_SQL);
+	x(&c, Status::CREATE_TABLE_SQL);
+	x(&c, Task::CREATE_TABLE_SQL);
+	x(&c, Account::CREATE_TABLE_SQL);
+	x(&c, Member::CREATE_TABLE_SQL);
+	x(&c, Pet::CREATE_TABLE_SQL);
+	x(&c, Customer::CREATE_TABLE_SQL);
+	x(&c, Customer::CREATE_INDEX_SQL);
+	x(&c, Customer::CREATE_TABLE_LOG_SQL);
+	x(&c, Pet::CREATE_INDEX_SQL);
+	x(&c, Order::CREATE_TABLE_SQL);
+	x(&c, Order::CREATE_INDEX_SQL);
+	x(&c, Order::CREATE_TABLE_LOG_SQL);
+	x(&c, QuoteAll::CREATE_TABLE_SQL);
+	x(&c, Note::CREATE_TABLE_SQL);
+	x(&c, Shop::CREATE_TABLE_SQL);
+	x(&c, Event::CREATE_TABLE_SQL);
+	x(&c, Stock::CREATE_TABLE_SQL);
+	x(&c, Stock::CREATE_TABLE_LOG_SQL);
+	x(&c, Depot::CREATE_TABLE_SQL);
+	x(&c, Depot::CREATE_TABLE_LOG_SQL);
+	x(&c, Bin::CREATE_TABLE_SQL);
+	x(&c, Bin::CREATE_TABLE_LOG_SQL);
+	x(&c, Ledger::CREATE_TABLE_SQL);
+	x(&c, Ledger::CREATE_TABLE_LOG_SQL);
+	assert!(
+		c.execute("INSERT INTO double (id,num) VALUES (1,-1)", [])
+			.is_err()
+	);
+	assert!(
+		c.execute("INSERT INTO double (id,num) VALUES (1,4294967296)", [])
+			.is_err()
+	);
+	x(&c, Shop::CREATE_TABLE_LOG_SQL);
+	x(&c, Label::CREATE_TABLE_SQL);
+	Label {
+		name: "new".to_owned(),
+		color: "red".to_owned(),
+	}
+	.insert_sync(&c)?;
+	x(&c, Login::CREATE_TABLE_SQL);
+	x(&c, Login::CREATE_INDEX_SQL);
+	let login = "INSERT INTO login (tenant,nick,email,created_at,deleted_at) VALUES (1,'a',?,0,?)";
+	c.execute(login, ("a@b.c", None::<f64>))?;
+	c.execute(login, ("a@b.c", Some(1.0)))?;
+	assert!(c.execute(login, ("A@b.c", None::<f64>)).is_err());
+
+	x(&c, Record::DROP_TABLE_LOG_SQL);
+	x(&c, Record::DROP_INDEX_SQL);
+	x(&c, Record::DROP_TABLE_SQL);
+	x(&c, Stock::DROP_TABLE_LOG_SQL);
+	x(&c, Stock::DROP_TABLE_SQL);
+	x(&c, Status::DROP_TABLE_SQL);
+	let left: i64 = c.query_row(
+		"SELECT count(*) FROM sqlite_schema WHERE name LIKE 'record%' OR name LIKE 'stock%' OR name='status'",
+		[],
+		|row| row.get(0),
+	)?;
+	assert_eq!(left, 0);
 
 	Ok(())
 }
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn rusqlite() -> Result<(), rusqlite::Error> {
 	let c = rusqlite::Connection::open_in_memory()?;
 	c.execute_batch(Single::CREATE_TABLE_SQL)?;
@@ -217,11 +861,454 @@ fn rusqlite() -> Result<(), rusqlite::Error> {
 	r.name = "you".to_owned();
 	let ok = r.update_sync(&c)?;
 	assert!(ok);
+	let r2 = Record::get_by_id_sync(id, &c)?;
+	assert_eq!(r2.name, "you");
+	let r2 = Record::get_by_name_sync("you", &c)?;
+	assert_eq!(r2.id, id);
+	let mut stmt = c.prepare(Record::SELECT_SQL)?;
+	let rows = stmt
+		.query_map([], Record::from_row_sync)?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(rows.len(), 1);
+	assert_eq!(rows[0].name, "you");
+	let mut stmt = c.prepare(MapRecord::SELECT_AS_SQL)?;
+	let rows = stmt
+		.query_map([], MapRecord::from_row_by_name_sync)?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(rows.len(), 1);
+	assert_eq!(rows[0].note, "you; note: ");
+	assert!(matches!(
+		Record::get_by_id_sync(0, &c),
+		Err(rusqlite::Error::QueryReturnedNoRows)
+	));
+	let r0 = Record {
+		id: 0,
+		..Default::default()
+	};
+	assert!(matches!(
+		r0.update_sync(&c),
+		Err(rusqlite::Error::ToSqlConversionFailure(e)) if e.to_string() == "id 0 < 1"
+	));
+	let ok = r.delete_sync(&c)?;
+	assert!(ok);
+	let ok = Record::delete_by_name_sync("you", &c)?;
+	assert!(!ok);
+
+	let mut d = Double { id: 0, num: 1 };
+	let id = d.upsert_sync(&c)?;
+	d.id = id;
+	d.num = 2;
+	assert_eq!(d.upsert_sync(&c)?, id);
+	let (count, num): (i64, u32) =
+		c.query_row("SELECT count(*),max(num) FROM double", [], |row| {
+			Ok((row.get(0)?, row.get(1)?))
+		})?;
+	assert_eq!((count, num), (1, 2));
+
+	d.num = 7;
+	assert!(d.update_num_sync(&c)?);
+	d.num = 8;
+	assert!(d.update_columns_sync(&[DoubleColumn::Num], &c)?);
+	assert!(!d.update_columns_sync(&[], &c)?);
+	let num: u32 = c.query_row("SELECT num FROM double WHERE id=?", [id], |row| row.get(0))?;
+	assert_eq!(num, 8);
+
+	let ds = [Double { id: 0, num: 3 }, Double { id: 9, num: 4 }];
+	let ids = Double::insert_many_sync(&ds, &c)?;
+	assert_eq!(ids, [id + 1, 9]);
+	let ds = [Double { id: 0, num: 5 }, Double { id: 9, num: 6 }];
+	assert!(Double::insert_many_sync(&ds, &c).is_err());
+	let count: i64 = c.query_row("SELECT count(*) FROM double", [], |row| row.get(0))?;
+	assert_eq!(count, 3);
+
+	c.execute_batch(TextPk::CREATE_TABLE_SQL)?;
+	let mut t = TextPk {
+		code: "a".to_owned(),
+		name: "first".to_owned(),
+	};
+	t.insert_sync(&c)?;
+	t.name = "second".to_owned();
+	assert!(t.update_sync(&c)?);
+	assert_eq!(TextPk::get_by_code_sync("a", &c)?.name, "second");
+
+	c.execute_batch(Status::CREATE_TABLE_SQL)?;
+	c.execute_batch(Task::CREATE_TABLE_SQL)?;
+	let t = Task {
+		id: 0,
+		status: Status::Inactive,
+	};
+	let id = t.insert_sync(&c)?;
+	let status: String =
+		c.query_row("SELECT status FROM task WHERE id=?", [id], |row| row.get(0))?;
+	assert_eq!(status, "Inactive");
+	let t = Task::get_by_id_sync(id, &c)?;
+	assert_eq!(t.status, Status::Inactive);
+
+	c.execute_batch(Account::CREATE_TABLE_SQL)?;
+	let a = Account {
+		balance: 10,
+		version: 5,
+		..Default::default()
+	};
+	let id = a.insert_sync(&c)?;
+	let mut a = Account::get_by_id_sync(id, &c)?;
+	assert_eq!(a.version, 0);
+	let mut stale = Account::get_by_id_sync(id, &c)?;
+	a.balance = 20;
+	assert!(a.update_sync(&c)?);
+	stale.balance = 30;
+	assert!(matches!(
+		stale.update_sync(&c),
+		Err(rusqlite::Error::QueryReturnedNoRows)
+	));
+	stale.id = 0;
+	assert!(matches!(
+		stale.update_sync(&c),
+		Err(rusqlite::Error::ToSqlConversionFailure(_))
+	));
+	let a = Account::get_by_id_sync(id, &c)?;
+	assert_eq!((a.balance, a.version), (20, 1));
+	c.execute("DELETE FROM account WHERE id=?", [id])?;
+	assert!(!a.update_sync(&c)?);
+
+	c.execute_batch(Pet::CREATE_TABLE_SQL)?;
+	let p = Pet {
+		name: "rex".to_owned(),
+		chip: "123".to_owned(),
+		owner: single_pk_id,
+		kind: "dog".to_owned(),
+		..Default::default()
+	};
+	let id = p.insert_sync(&c)?;
+	assert_eq!(Pet::get_by_id_sync(id, &c)?.chip, "123");
+	assert_eq!(Pet::get_by_name_sync("rex", &c)?.id, id);
+	let p = Pet {
+		name: String::new(),
+		..Default::default()
+	};
+	assert!(p.insert_sync(&c).is_err());
+
+	c.execute_batch(Customer::CREATE_TABLE_SQL)?;
+	c.execute_batch(Customer::CREATE_TABLE_LOG_SQL)?;
+	let mut cu = Customer {
+		id: 0,
+		full_name: "Ann".to_owned(),
+		group_id: single_pk_id,
+	};
+	cu.id = cu.insert_sync(&c)?;
+	let mut cn = CustomerName::get_by_id_sync(cu.id, &c)?;
+	assert_eq!(cn.name, "Ann");
+	cn.name = "Bob".to_owned();
+	assert!(cn.update_sync(&c)?);
+	assert_eq!(Customer::get_by_full_name_sync("Bob", &c)?.id, cu.id);
+	cu.full_name = "Cid".to_owned();
+	assert!(cu.update_columns_sync(&[CustomerColumn::FullName], &c)?);
+	let mut stmt = c.prepare(Customer::SELECT_SQL)?;
+	let rows = stmt
+		.query_map([], Customer::from_row_by_name_sync)?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(rows[0].full_name, "Cid");
+
+	c.execute_batch(Order::CREATE_TABLE_SQL)?;
+	c.execute_batch(Order::CREATE_TABLE_LOG_SQL)?;
+	let mut o = Order {
+		id: 0,
+		group: "a".to_owned(),
+		r#type: "t".to_owned(),
+		r#where: single_pk_id,
+	};
+	o.id = o.insert_sync(&c)?;
+	o.r#type = "u".to_owned();
+	assert!(o.update_type_sync(&c)?);
+	o.r#where = single_pk_id;
+	assert!(o.update_columns_sync(&[OrderColumn::Group, OrderColumn::Where], &c)?);
+	assert_eq!(Order::get_by_group_sync("a", &c)?.r#type, "u");
+	o.r#type = "v".to_owned();
+	assert_eq!(o.upsert_sync(&c)?, o.id);
+	let mut stmt = c.prepare(Order::SELECT_SQL)?;
+	let rows = stmt
+		.query_map([], Order::from_row_by_name_sync)?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(rows[0].r#type, "v");
+	assert!(Order::delete_by_group_sync("a", &c)?);
+
+	c.execute_batch(Note::CREATE_TABLE_SQL)?;
+	let mut n = Note {
+		id: 0,
+		text: "a".to_owned(),
+		created: "ignored".to_owned(),
+		cache: Some(1),
+		..Default::default()
+	};
+	n.id = n.insert_sync(&c)?;
+	n.text = "b".to_owned();
+	assert!(n.update_sync(&c)?);
+	assert!(matches!(
+		n.update_columns_sync(&[NoteColumn::Text, NoteColumn::Len], &c),
+		Err(rusqlite::Error::InvalidColumnName(msg)) if msg == "len is not updatable"
+	));
+	assert!(n.update_columns_sync(&[NoteColumn::Text], &c)?);
+	let n2 = Note::get_by_id_sync(n.id, &c)?;
+	assert_eq!((n2.text.as_str(), n2.cache), ("b", None));
+	assert_eq!((n2.len, n2.upper.as_str()), (1, "B"));
+	assert_ne!(n2.created, "ignored");
+	let mut stmt = c.prepare(Note::SELECT_SQL)?;
+	let rows = stmt
+		.query_map([], Note::from_row_by_name_sync)?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(rows[0].created, n2.created);
+
+	c.execute_batch(Member::CREATE_TABLE_SQL)?;
+	let mut m = Member {
+		person: 1,
+		club: 2,
+		seat: 3,
+		role: "member".to_owned(),
+	};
+	m.insert_sync(&c)?;
+	m.role = "chair".to_owned();
+	assert!(m.update_sync(&c)?);
+	assert_eq!(Member::get_by_person_and_club_sync(1, 2, &c)?.role, "chair");
+	assert_eq!(Member::get_by_club_and_seat_sync(2, 3, &c)?.person, 1);
+	m.role = "guest".to_owned();
+	assert_eq!(m.upsert_sync(&c)?, 1);
+	assert_eq!(Member::get_by_person_and_club_sync(1, 2, &c)?.role, "guest");
+	assert!(!Member::delete_by_person_and_club_sync(2, 1, &c)?);
+	assert!(m.delete_sync(&c)?);
+
+	c.execute_batch(Shop::CREATE_TABLE_SQL)?;
+	let mut s = Shop {
+		name: "corner".to_owned(),
+		address: Address {
+			street: "Main St".to_owned(),
+			city: "Vienna".to_owned(),
+			zip: Some(1010),
+		},
+		..Default::default()
+	};
+	s.id = s.insert_sync(&c)?;
+	s.billing.city = "Graz".to_owned();
+	assert!(s.update_sync(&c)?);
+	s.ver += 1;
+	let s2 = Shop::get_by_name_sync("corner", &c)?;
+	assert_eq!(
+		(&s2.address, &s2.billing, s2.ver),
+		(&s.address, &s.billing, 1)
+	);
+	s.name = "shop".to_owned();
+	assert!(matches!(
+		s.update_columns_sync(&[ShopColumn::Name, ShopColumn::Ver], &c),
+		Err(rusqlite::Error::InvalidColumnName(msg)) if msg == "ver is not updatable"
+	));
+	assert!(s.update_columns_sync(&[ShopColumn::Name], &c)?);
+	s.address.zip = None;
+	assert_eq!(s.upsert_sync(&c)?, s.id);
+	let mut stmt = c.prepare(Shop::SELECT_SQL)?;
+	let rows = stmt
+		.query_map([], Shop::from_row_by_name_sync)?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!((rows[0].name.as_str(), rows[0].address.zip), ("shop", None));
+	assert_eq!(rows[0].billing.city, "Graz");
+	let rows = stmt
+		.query_map([], Shop::from_row_sync)?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(
+		(rows[0].ver, &rows[0].address.street),
+		(3, &s.address.street)
+	);
+
+	c.execute_batch(Stock::CREATE_TABLE_SQL)?;
+	c.execute_batch(Stock::CREATE_TABLE_LOG_SQL)?;
+	let mut st = Stock {
+		id: 0,
+		item: "nail".to_owned(),
+		qty: 1,
+	};
+	st.id = st.insert_sync(&c)?;
+	st.qty = 2;
+	st.update_sync(&c)?;
+	let id = Stock {
+		id: 0,
+		item: "screw".to_owned(),
+		qty: 3,
+	}
+	.insert_sync(&c)?;
+	st.delete_sync(&c)?;
+	let mut stmt = c.prepare("SELECT log_op,qty,log_at FROM stock_log ORDER BY log_id")?;
+	let log = stmt
+		.query_map([], |row| {
+			Ok((
+				row.get::<_, String>(0)?,
+				row.get::<_, i32>(1)?,
+				row.get::<_, f64>(2)?,
+			))
+		})?
+		.collect::<Result<Vec<_>, _>>()?;
+	let ops: Vec<_> = log.iter().map(|(op, qty, _)| (op.as_str(), *qty)).collect();
+	assert_eq!(ops, [("I", 1), ("U", 1), ("I", 3), ("D", 2)]);
+	assert!(log.iter().all(|(_, _, at)| *at > 1e9));
+	let ids = c
+		.prepare("SELECT id FROM stock_log WHERE log_op='I' ORDER BY log_id")?
+		.query_map([], |row| row.get::<_, i64>(0))?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(ids, [st.id, id]);
+
+	c.execute_batch(Depot::CREATE_TABLE_SQL)?;
+	c.execute_batch(Depot::CREATE_TABLE_LOG_SQL)?;
+	let mut d = Depot {
+		id: 0,
+		address: Address::default(),
+		ver: 0,
+	};
+	d.id = d.insert_sync(&c)?;
+	d.update_sync(&c)?;
+	d.ver += 1;
+	d.address.zip = Some(8010);
+	d.update_sync(&c)?;
+	let zips = c
+		.prepare("SELECT address_zip,ver FROM depot_log")?
+		.query_map([], |row| {
+			Ok((row.get::<_, Option<u16>>(0)?, row.get::<_, i64>(1)?))
+		})?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(zips, [(None, 1)]);
+
+	c.execute_batch(Bin::CREATE_TABLE_SQL)?;
+	c.execute_batch(Bin::CREATE_TABLE_LOG_SQL)?;
+	let mut b = Bin {
+		id: 0,
+		item: "screw".to_owned(),
+		qty: 1,
+	};
+	b.id = b.insert_sync(&c)?;
+	b.update_sync(&c)?;
+	b.qty = 2;
+	b.update_sync(&c)?;
+	b.delete_sync(&c)?;
+	let log = c
+		.prepare("SELECT log_op,log_mask,id,item,qty FROM bin_log ORDER BY log_id")?
+		.query_map([], |row| {
+			Ok((
+				row.get::<_, String>(0)?,
+				row.get::<_, i64>(1)?,
+				row.get::<_, i64>(2)?,
+				row.get::<_, Option<String>>(3)?,
+				row.get::<_, Option<i32>>(4)?,
+			))
+		})?
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(
+		log,
+		[
+			("U".to_owned(), 4, b.id, None, Some(1)),
+			("D".to_owned(), 7, b.id, Some("screw".to_owned()), Some(2)),
+		]
+	);
+
+	c.execute_batch(Shelf::CREATE_TABLE_SQL)?;
+	c.execute_batch(Shelf::CREATE_TABLE_LOG_SQL)?;
+	let mut s = Shelf {
+		rack: 1,
+		slot: 2,
+		qty: 3,
+	};
+	s.insert_sync(&c)?;
+	s.qty = 4;
+	s.update_sync(&c)?;
+	// the columns of a table constraint PRIMARY KEY are always stored
+	let log: (i64, Option<i64>, Option<i64>, Option<i32>) =
+		c.query_row("SELECT log_mask,rack,slot,qty FROM shelf_log", [], |row| {
+			Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+		})?;
+	assert_eq!(log, (4, Some(1), Some(2), Some(3)));
+
+	c.execute_batch(Price::CREATE_TABLE_SQL)?;
+	c.execute_batch(Price::CREATE_TABLE_LOG_SQL)?;
+	let now = || {
+		c.query_row("SELECT unixepoch('subsec')", [], |row| row.get(0))
+			.unwrap()
+	};
+	let before = log_pause(now);
+	let mut p = Price {
+		id: 0,
+		amount: 1,
+		ver: 0,
+	};
+	p.id = p.insert_sync(&c)?;
+	let at1 = log_pause(now);
+	p.amount = 2;
+	p.update_sync(&c)?;
+	p.ver += 1;
+	let at2 = log_pause(now);
+	p.amount = 3;
+	p.update_sync(&c)?;
+	let amounts = |rows: Vec<Price>| rows.iter().map(|p| p.amount).collect::<Vec<_>>();
+	assert_eq!(amounts(Price::history_by_id_sync(p.id, &c)?), [1, 1, 2]);
+	assert!(Price::as_of_by_id_sync(p.id, before, &c)?.is_none());
+	let as_of = |at| Price::as_of_by_id_sync(p.id, at, &c).map(|p| p.map(|p| (p.amount, p.ver)));
+	assert_eq!(as_of(at1)?, Some((1, 0)));
+	assert_eq!(as_of(at2)?, Some((2, 1)));
+	assert_eq!(as_of(f64::MAX)?, Some((3, 2)));
+	assert!(Price::restore_by_id_sync(p.id, at1, &c)?);
+	assert_eq!(as_of(f64::MAX)?, Some((1, 3)));
+	assert!(!Price::restore_by_id_sync(p.id, before, &c)?);
+	c.execute("DELETE FROM price WHERE id=?", [p.id])?;
+	assert!(!Price::restore_by_id_sync(p.id, at1, &c)?);
+
+	c.execute_batch(Fee::CREATE_TABLE_SQL)?;
+	c.execute_batch(Fee::CREATE_TABLE_LOG_SQL)?;
+	let before = log_pause(now);
+	let mut f = Fee { id: 0, amount: 1 };
+	f.id = f.insert_sync(&c)?;
+	let at1 = log_pause(now);
+	f.amount = 2;
+	f.update_sync(&c)?;
+	let amount = |at| Fee::as_of_by_id_sync(f.id, at, &c).map(|f| f.map(|f| f.amount));
+	// without log_insert the row before its insert is the first logged state
+	assert_eq!(amount(before)?, Some(1));
+	assert_eq!(amount(at1)?, Some(1));
+	c.execute("DELETE FROM fee WHERE id=?", [f.id])?;
+	assert!(!Fee::restore_by_id_sync(f.id, at1, &c)?);
+
+	c.execute_batch(Event::CREATE_TABLE_SQL)?;
+	let mut e = event();
+	e.id = e.insert_sync(&c)?;
+	assert_eq!(Event::get_by_id_sync(e.id, &c)?, e);
+	assert!(
+		c.execute(
+			"INSERT INTO event (uuid,day,at,start,data) SELECT uuid,day,at,start,'{' FROM event",
+			[]
+		)
+		.is_err()
+	);
+
+	c.execute_batch(Invoice::CREATE_TABLE_SQL)?;
+	let total: rust_decimal::Decimal = "12.50".parse().unwrap();
+	c.execute(
+		"INSERT INTO invoice (total) VALUES (?)",
+		[total.to_string()],
+	)?;
+	let read: String = c.query_row("SELECT total FROM invoice", [], |row| row.get(0))?;
+	assert_eq!(read.parse::<rust_decimal::Decimal>().unwrap(), total);
+	assert_eq!(read, "12.50");
 
 	Ok(())
 }
 
+fn event() -> Event {
+	Event {
+		id: 0,
+		uuid: uuid::Uuid::from_u128(0x1234),
+		day: chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap(),
+		at: chrono::DateTime::from_timestamp(1_800_000_000, 0).unwrap(),
+		start: time::OffsetDateTime::from_unix_timestamp(1_800_000_000).unwrap(),
+		end: Some(time::Date::from_calendar_date(2026, time::Month::October, 19).unwrap()),
+		data: serde_json::json!({"seats": 3, "tags": ["a", "b"]}),
+	}
+}
+
 #[tokio::test]
+#[allow(clippy::field_reassign_with_default)]
 async fn sqlx() -> Result<(), sqlx::Error> {
 	use sqlx::{Connection, Executor, SqliteConnection};
 	let mut c = SqliteConnection::connect(":memory:").await?;
@@ -247,6 +1334,230 @@ async fn sqlx() -> Result<(), sqlx::Error> {
 	r.name = "you".to_owned();
 	let ok = r.update(&mut c).await?;
 	assert!(ok);
+	let r0 = Record {
+		id: 0,
+		..Default::default()
+	};
+	assert!(matches!(
+		r0.update(&mut c).await,
+		Err(sqlx::Error::InvalidArgument(m)) if m == "id 0 < 1"
+	));
+	let ok = Record::delete_by_name("you", &mut c).await?;
+	assert!(ok);
+	let ok = r.delete(&mut c).await?;
+	assert!(!ok);
+
+	let mut d = Double { id: 0, num: 1 };
+	let id = d.upsert(&mut c).await?;
+	d.id = id;
+	d.num = 2;
+	assert_eq!(d.upsert(&mut c).await?, id);
+	let mut d = Double::get_by_id(id, &mut c).await?;
+	assert_eq!(d.num, 2);
+	d.num = 7;
+	assert!(d.update_num(&mut c).await?);
+	d.num = 8;
+	assert!(matches!(
+		d.update_columns(&[DoubleColumn::Num, DoubleColumn::Id], &mut c).await,
+		Err(sqlx::Error::InvalidArgument(msg)) if msg == "id is not updatable"
+	));
+	assert!(d.update_columns(&[DoubleColumn::Num], &mut c).await?);
+	assert_eq!(Double::get_by_id(id, &mut c).await?.num, 8);
+
+	let ds = [Double { id: 0, num: 3 }, Double { id: 9, num: 4 }];
+	let ids = Double::insert_many(&ds, &mut c).await?;
+	assert_eq!(ids, [id + 1, 9]);
+	let ds = [Double { id: 0, num: 5 }, Double { id: 9, num: 6 }];
+	assert!(Double::insert_many(&ds, &mut c).await.is_err());
+	let count: i64 = sqlx::query_scalar("SELECT count(*) FROM double")
+		.fetch_one(&mut c)
+		.await?;
+	assert_eq!(count, 3);
+
+	c.execute(TextPk::CREATE_TABLE_SQL).await?;
+	let mut t = TextPk {
+		code: "a".to_owned(),
+		name: "first".to_owned(),
+	};
+	t.insert(&mut c).await?;
+	t.name = "second".to_owned();
+	assert!(t.update(&mut c).await?);
+	assert_eq!(TextPk::get_by_code("a", &mut c).await?.name, "second");
+
+	c.execute(Status::CREATE_TABLE_SQL).await?;
+	c.execute(Task::CREATE_TABLE_SQL).await?;
+	let t = Task {
+		id: 0,
+		status: Status::Inactive,
+	};
+	let id = t.insert(&mut c).await?;
+	let t = Task::get_by_id(id, &mut c).await?;
+	assert_eq!(t.status, Status::Inactive);
+
+	c.execute(Account::CREATE_TABLE_SQL).await?;
+	let a = Account {
+		balance: 10,
+		..Default::default()
+	};
+	let id = a.insert(&mut c).await?;
+	let mut a = Account::get_by_id(id, &mut c).await?;
+	let mut stale = Account::get_by_id(id, &mut c).await?;
+	a.balance = 20;
+	assert!(a.update_balance(&mut c).await?);
+	stale.balance = 30;
+	assert!(matches!(
+		stale.update(&mut c).await,
+		Err(sqlx::Error::RowNotFound)
+	));
+	stale.id = 0;
+	assert!(matches!(
+		stale.update(&mut c).await,
+		Err(sqlx::Error::InvalidArgument(_))
+	));
+	let a = Account::get_by_id(id, &mut c).await?;
+	assert_eq!((a.balance, a.version), (20, 1));
+	sqlx::query("DELETE FROM account WHERE id=?")
+		.bind(id)
+		.execute(&mut c)
+		.await?;
+	assert!(!a.update(&mut c).await?);
+	assert!(!a.update_balance(&mut c).await?);
+
+	c.execute(Customer::CREATE_TABLE_SQL).await?;
+	let mut cu = Customer {
+		id: 0,
+		full_name: "Ann".to_owned(),
+		group_id: single_pk_id,
+	};
+	cu.id = cu.insert(&mut c).await?;
+	cu.full_name = "Bob".to_owned();
+	assert!(cu.update(&mut c).await?);
+	assert_eq!(Customer::get_by_id(cu.id, &mut c).await?.full_name, "Bob");
+	let rows: Vec<Customer> = sqlx::query_as(Customer::SELECT_SQL)
+		.fetch_all(&mut c)
+		.await?;
+	assert_eq!(rows[0].full_name, "Bob");
+
+	c.execute(Order::CREATE_TABLE_SQL).await?;
+	let mut o = Order {
+		id: 0,
+		group: "a".to_owned(),
+		r#type: "t".to_owned(),
+		r#where: single_pk_id,
+	};
+	o.id = o.insert(&mut c).await?;
+	o.r#type = "u".to_owned();
+	assert!(o.update(&mut c).await?);
+	assert!(o.update_columns(&[OrderColumn::Type], &mut c).await?);
+	assert_eq!(Order::get_by_group("a", &mut c).await?.r#type, "u");
+	let rows: Vec<Order> = sqlx::query_as(Order::SELECT_SQL).fetch_all(&mut c).await?;
+	assert_eq!(rows[0].r#where, single_pk_id);
+	assert!(o.delete(&mut c).await?);
+
+	c.execute(Note::CREATE_TABLE_SQL).await?;
+	let mut n = Note {
+		id: 0,
+		text: "a".to_owned(),
+		created: "ignored".to_owned(),
+		cache: Some(1),
+		..Default::default()
+	};
+	n.id = n.insert(&mut c).await?;
+	n.text = "b".to_owned();
+	assert!(matches!(
+		n.update_columns(&[NoteColumn::Text, NoteColumn::Created], &mut c)
+			.await,
+		Err(sqlx::Error::InvalidArgument(msg)) if msg == "created is not updatable"
+	));
+	assert!(n.update_columns(&[NoteColumn::Text], &mut c).await?);
+	let n2 = Note::get_by_id(n.id, &mut c).await?;
+	assert_eq!((n2.text.as_str(), n2.cache), ("b", None));
+	assert_eq!((n2.len, n2.upper.as_str()), (1, "B"));
+	assert_ne!(n2.created, "ignored");
+
+	c.execute(Member::CREATE_TABLE_SQL).await?;
+	let mut m = Member {
+		person: 1,
+		club: 2,
+		seat: 3,
+		role: "member".to_owned(),
+	};
+	m.insert(&mut c).await?;
+	m.role = "chair".to_owned();
+	assert!(m.update_role(&mut c).await?);
+	let m2 = Member::get_by_person_and_club(1, 2, &mut c).await?;
+	assert_eq!(m2.role, "chair");
+	assert_eq!(Member::get_by_club_and_seat(2, 3, &mut c).await?.person, 1);
+	assert!(Member::delete_by_club_and_seat(2, 3, &mut c).await?);
+	assert!(!m.delete(&mut c).await?);
+
+	c.execute(Shop::CREATE_TABLE_SQL).await?;
+	let mut s = Shop {
+		name: "corner".to_owned(),
+		billing: Address {
+			street: "Main St".to_owned(),
+			city: "Vienna".to_owned(),
+			zip: None,
+		},
+		..Default::default()
+	};
+	s.id = s.insert(&mut c).await?;
+	s.address.zip = Some(8010);
+	assert!(s.update(&mut c).await?);
+	s.ver += 1;
+	let s2 = Shop::get_by_id(s.id, &mut c).await?;
+	assert_eq!(
+		(&s2.address, &s2.billing, s2.ver),
+		(&s.address, &s.billing, 1)
+	);
+	s.billing.city = "Graz".to_owned();
+	assert_eq!(s.upsert(&mut c).await?, s.id);
+	let s3 = Shop::get_by_name("corner", &mut c).await?;
+	assert_eq!((s3.billing.city.as_str(), s3.ver), ("Graz", 2));
+
+	c.execute(Price::CREATE_TABLE_SQL).await?;
+	c.execute(Price::CREATE_TABLE_LOG_SQL).await?;
+	let mut p = Price {
+		id: 0,
+		amount: 1,
+		ver: 0,
+	};
+	p.id = p.insert(&mut c).await?;
+	let (at,): (f64,) = sqlx::query_as("SELECT unixepoch('subsec')")
+		.fetch_one(&mut c)
+		.await?;
+	let at1 = log_pause(|| at);
+	p.amount = 2;
+	p.update(&mut c).await?;
+	let history = Price::history_by_id(p.id, &mut c).await?;
+	assert_eq!(history.iter().map(|p| p.amount).collect::<Vec<_>>(), [1, 1]);
+	let p1 = Price::as_of_by_id(p.id, at1, &mut c).await?;
+	assert_eq!(p1.map(|p| p.amount), Some(1));
+	assert!(Price::restore_by_id(p.id, at1, &mut c).await?);
+	let p2 = Price::as_of_by_id(p.id, f64::MAX, &mut c).await?;
+	assert_eq!(p2.map(|p| (p.amount, p.ver)), Some((1, 2)));
+	sqlx::query("DELETE FROM price WHERE id=?")
+		.bind(p.id)
+		.execute(&mut c)
+		.await?;
+	assert!(!Price::restore_by_id(p.id, at1, &mut c).await?);
+
+	c.execute(Event::CREATE_TABLE_SQL).await?;
+	let mut e = event();
+	e.id = e.insert(&mut c).await?;
+	assert_eq!(Event::get_by_id(e.id, &mut c).await?, e);
+
+	// a table without the PRIMARY KEY of the struct
+	let mut c = SqliteConnection::connect(":memory:").await?;
+	c.execute(
+		"CREATE TABLE double (id INTEGER, num INTEGER); INSERT INTO double VALUES (1,1),(1,2);",
+	)
+	.await?;
+	let d = Double { id: 1, num: 3 };
+	assert!(matches!(
+		d.update(&mut c).await,
+		Err(sqlx::Error::InvalidArgument(_))
+	));
 
 	Ok(())
 }